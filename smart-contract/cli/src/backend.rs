@@ -0,0 +1,38 @@
+use soroban_sdk::{Address, BytesN, Env, IntoVal, TryFromVal, Val, Vec};
+
+use crate::error::CliError;
+
+/// Where contract calls go: the local sandbox or a Soroban RPC server.
+pub trait Backend {
+    /// Host used to build arguments and decode results.
+    fn env(&self) -> &Env;
+
+    fn contract_id(&self) -> &Address;
+
+    /// Network the contract runs on, which device signatures are bound to.
+    fn network_id(&self) -> BytesN<32>;
+
+    /// Current ledger time in Unix seconds.
+    fn timestamp(&self) -> u64;
+
+    /// Resolve an identity name or a `G…`/`C…` strkey to an address.
+    fn address(&self, name_or_strkey: &str) -> Result<Address, CliError>;
+
+    /// Invoke a contract function and return its result undecoded.
+    fn invoke(&self, function: &str, args: Vec<Val>) -> Result<Val, CliError>;
+}
+
+/// Invoke `function` with `args` and decode its result as `T`.
+pub fn call<T>(
+    backend: &dyn Backend,
+    function: &str,
+    args: impl IntoVal<Env, Vec<Val>>,
+) -> Result<T, CliError>
+where
+    T: TryFromVal<Env, Val>,
+{
+    let env = backend.env();
+    let result = backend.invoke(function, args.into_val(env))?;
+    T::try_from_val(env, &result)
+        .map_err(|_| CliError::Host(format!("`{function}` returned an unexpected value")))
+}
@@ -0,0 +1,1750 @@
+use std::path::PathBuf;
+
+use chainlogistics::{
+    ActorGrant, ActorPage, ActorRole, AdminAction, AdminCouncil, AdminHandover, AdminProposal,
+    Attestation, CertIssuer, Device, EventChainReport, EventMerkleRoot, Lifecycle, Lineage,
+    PauseRecord, PauseScope, PendingTransfer, Product, ProductPage, ProductStats, ProductVersion,
+    Recall, RecallScope, RecallSeverity, RecallStatus, TelemetryReading, TelemetryThresholds,
+    TrackingEvent, TrackingEventFilter, TrackingEventPage,
+};
+use chainlogistics_epcis::ImportedEvent;
+use chainlogistics_merkle::{to_hex, MerkleTree};
+use clap::{Args, Subcommand, ValueEnum};
+use ed25519_dalek::{Signer, SigningKey};
+use serde_json::{json, Value};
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{Address, BytesN, Env, IntoVal, Symbol, Val};
+
+use crate::backend::{call, Backend};
+use crate::error::CliError;
+use crate::input::{
+    self, load_file, LifecycleFile, LocationFile, ProductConfigFile, ProductConfigs,
+    ProductMetadataFile,
+};
+use crate::render::ToJson;
+
+const ZERO_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Environment variable holding a device's hex secret key.
+pub const DEVICE_KEY_VAR: &str = "CHAINLOGISTICS_DEVICE_KEY";
+
+// ─── Shared arguments ────────────────────────────────────────────────────────
+
+#[derive(Args, Debug)]
+pub struct LocationArgs {
+    /// Free-text location name.
+    #[arg(long = "location", default_value = "")]
+    pub name: String,
+    /// GS1 GLN or internal facility code.
+    #[arg(long, default_value = "")]
+    pub facility_id: String,
+    /// ISO 3166-1 alpha-2 country code.
+    #[arg(long, default_value = "")]
+    pub country: String,
+    #[arg(long, default_value = "")]
+    pub geohash: String,
+    /// Decimal degrees.
+    #[arg(long, requires = "longitude", allow_hyphen_values = true)]
+    pub latitude: Option<f64>,
+    /// Decimal degrees.
+    #[arg(long, requires = "latitude", allow_hyphen_values = true)]
+    pub longitude: Option<f64>,
+}
+
+impl LocationArgs {
+    fn to_contract(&self, env: &Env) -> Result<chainlogistics::Location, CliError> {
+        LocationFile {
+            name: self.name.clone(),
+            facility_id: self.facility_id.clone(),
+            country: self.country.clone(),
+            geohash: self.geohash.clone(),
+            latitude: self.latitude,
+            longitude: self.longitude,
+        }
+        .to_contract(env)
+    }
+}
+
+/// Where to read a device's ed25519 secret key from.
+#[derive(Args, Debug)]
+pub struct DeviceKeyArgs {
+    /// File holding the device secret key as hex. Falls back to
+    /// `$CHAINLOGISTICS_DEVICE_KEY`.
+    #[arg(long)]
+    pub secret_key_file: Option<PathBuf>,
+}
+
+impl DeviceKeyArgs {
+    fn signing_key(&self) -> Result<SigningKey, CliError> {
+        let secret = input::device_key(self.secret_key_file.as_deref(), DEVICE_KEY_VAR)?;
+        Ok(SigningKey::from_bytes(&secret))
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct PageArgs {
+    #[arg(long, default_value_t = 0)]
+    pub offset: u64,
+    #[arg(long, default_value_t = 20)]
+    pub limit: u64,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Role {
+    Manager,
+    Carrier,
+    Warehouse,
+    Inspector,
+    Retailer,
+}
+
+impl From<Role> for ActorRole {
+    fn from(role: Role) -> Self {
+        match role {
+            Role::Manager => ActorRole::Manager,
+            Role::Carrier => ActorRole::Carrier,
+            Role::Warehouse => ActorRole::Warehouse,
+            Role::Inspector => ActorRole::Inspector,
+            Role::Retailer => ActorRole::Retailer,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+impl From<Severity> for RecallSeverity {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Low => RecallSeverity::Low,
+            Severity::Medium => RecallSeverity::Medium,
+            Severity::High => RecallSeverity::High,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Scope {
+    All,
+    Registrations,
+    EventWrites,
+    Transfers,
+    Product,
+    Category,
+}
+
+#[derive(Args, Debug)]
+pub struct ScopeArgs {
+    pub scope: Scope,
+    /// Product ID or category name, for the `product` and `category` scopes.
+    #[arg(long)]
+    pub target: Option<String>,
+}
+
+impl ScopeArgs {
+    fn to_contract(&self, env: &Env) -> Result<PauseScope, CliError> {
+        let target = || match &self.target {
+            Some(t) => Ok(soroban_sdk::String::from_str(env, t)),
+            None => Err(CliError::Input(format!(
+                "--target is required for {:?}",
+                self.scope
+            ))),
+        };
+        Ok(match self.scope {
+            Scope::All => PauseScope::All,
+            Scope::Registrations => PauseScope::Registrations,
+            Scope::EventWrites => PauseScope::EventWrites,
+            Scope::Transfers => PauseScope::Transfers,
+            Scope::Product => PauseScope::Product(target()?),
+            Scope::Category => PauseScope::Category(target()?),
+        })
+    }
+}
+
+/// Exactly one of explicit products, a category or an origin.
+#[derive(Args, Debug)]
+pub struct RecallScopeArgs {
+    #[arg(long, value_delimiter = ',', required_unless_present_any = ["category", "origin"])]
+    pub products: Vec<String>,
+    #[arg(long, conflicts_with_all = ["products", "origin"])]
+    pub category: Option<String>,
+    #[arg(long, conflicts_with_all = ["products", "category"])]
+    pub origin: Option<String>,
+}
+
+impl RecallScopeArgs {
+    fn to_contract(&self, env: &Env) -> RecallScope {
+        let text = |s: &str| soroban_sdk::String::from_str(env, s);
+        match (&self.category, &self.origin) {
+            (Some(category), _) => RecallScope::Category(text(category)),
+            (_, Some(origin)) => RecallScope::Origin(text(origin)),
+            _ => RecallScope::Products(input::strings(env, &self.products)),
+        }
+    }
+}
+
+/// An admin action for the council to vote on.
+#[derive(Subcommand, Debug)]
+pub enum ActionCommand {
+    SetCouncil {
+        #[arg(long, value_delimiter = ',')]
+        signers: Vec<String>,
+        #[arg(long)]
+        threshold: u32,
+    },
+    Upgrade {
+        #[arg(long)]
+        wasm_hash: String,
+    },
+    Pause {
+        #[command(flatten)]
+        scope: ScopeArgs,
+        #[arg(long)]
+        reason: String,
+        /// Seconds until the pause lifts on its own; 0 for until unpaused.
+        #[arg(long, default_value_t = 0)]
+        duration: u64,
+    },
+    Unpause {
+        #[command(flatten)]
+        scope: ScopeArgs,
+    },
+    SetGuardian {
+        #[arg(long)]
+        guardian: String,
+    },
+    RemoveGuardian,
+    SetCertIssuer {
+        #[arg(long)]
+        issuer: String,
+        #[arg(long)]
+        name: String,
+        #[arg(long, value_delimiter = ',')]
+        cert_types: Vec<String>,
+    },
+    RemoveCertIssuer {
+        #[arg(long)]
+        issuer: String,
+    },
+    SetCategoryLifecycle {
+        #[arg(long)]
+        category: String,
+        /// JSON or TOML file with `initial` and `transitions`.
+        #[arg(long)]
+        lifecycle: PathBuf,
+    },
+    RemoveCategoryLifecycle {
+        #[arg(long)]
+        category: String,
+    },
+    InitiateRecall {
+        #[command(flatten)]
+        scope: RecallScopeArgs,
+        #[arg(long)]
+        reason: String,
+        #[arg(long, value_enum)]
+        severity: Severity,
+    },
+}
+
+impl ActionCommand {
+    fn to_contract(&self, backend: &dyn Backend) -> Result<AdminAction, CliError> {
+        let env = backend.env();
+        let text = |s: &str| soroban_sdk::String::from_str(env, s);
+        Ok(match self {
+            ActionCommand::SetCouncil { signers, threshold } => {
+                let mut addresses = soroban_sdk::Vec::new(env);
+                for signer in signers {
+                    addresses.push_back(backend.address(signer)?);
+                }
+                AdminAction::SetCouncil(addresses, *threshold)
+            }
+            ActionCommand::Upgrade { wasm_hash } => {
+                AdminAction::Upgrade(input::hash(env, wasm_hash)?)
+            }
+            ActionCommand::Pause {
+                scope,
+                reason,
+                duration,
+            } => AdminAction::Pause(scope.to_contract(env)?, text(reason), *duration),
+            ActionCommand::Unpause { scope } => AdminAction::Unpause(scope.to_contract(env)?),
+            ActionCommand::SetGuardian { guardian } => {
+                AdminAction::SetGuardian(backend.address(guardian)?)
+            }
+            ActionCommand::RemoveGuardian => AdminAction::RemoveGuardian,
+            ActionCommand::SetCertIssuer {
+                issuer,
+                name,
+                cert_types,
+            } => AdminAction::SetCertIssuer(
+                backend.address(issuer)?,
+                text(name),
+                input::symbols(env, cert_types)?,
+            ),
+            ActionCommand::RemoveCertIssuer { issuer } => {
+                AdminAction::RemoveCertIssuer(backend.address(issuer)?)
+            }
+            ActionCommand::SetCategoryLifecycle {
+                category,
+                lifecycle,
+            } => AdminAction::SetCategoryLifecycle(
+                text(category),
+                load_file::<LifecycleFile>(lifecycle)?.to_contract(env)?,
+            ),
+            ActionCommand::RemoveCategoryLifecycle { category } => {
+                AdminAction::RemoveCategoryLifecycle(text(category))
+            }
+            ActionCommand::InitiateRecall {
+                scope,
+                reason,
+                severity,
+            } => AdminAction::InitiateRecall(
+                scope.to_contract(env),
+                text(reason),
+                RecallSeverity::from(*severity),
+            ),
+        })
+    }
+}
+
+// ─── Commands ────────────────────────────────────────────────────────────────
+
+/// One subcommand per contract entry point. Addresses may be given as an
+/// identity name or a strkey; hashes and keys as hex.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    // --- Admin ---
+    Init {
+        #[arg(long)]
+        admin: String,
+    },
+    GetAdmin,
+    IsPaused,
+    Pause {
+        #[arg(long)]
+        admin: String,
+    },
+    Unpause {
+        #[arg(long)]
+        admin: String,
+    },
+    /// Offer admin rights; the new admin accepts after the council timelock.
+    TransferAdmin {
+        #[arg(long)]
+        admin: String,
+        #[arg(long)]
+        new_admin: String,
+    },
+    AcceptAdmin {
+        #[arg(long)]
+        new_admin: String,
+    },
+    CancelAdminTransfer {
+        #[arg(long)]
+        admin: String,
+    },
+    GetAdminHandover,
+
+    // --- Products ---
+    /// Register a product from a JSON or TOML `ProductConfig` file.
+    RegisterProduct {
+        #[arg(long)]
+        owner: String,
+        #[arg(long)]
+        config: PathBuf,
+    },
+    DeactivateProduct {
+        #[arg(long)]
+        owner: String,
+        #[arg(long)]
+        product: String,
+        #[arg(long)]
+        reason: String,
+    },
+    ReactivateProduct {
+        #[arg(long)]
+        owner: String,
+        #[arg(long)]
+        product: String,
+    },
+    /// Replace a product's editable fields from a JSON or TOML
+    /// `ProductMetadata` file.
+    UpdateProductMetadata {
+        #[arg(long)]
+        owner: String,
+        #[arg(long)]
+        product: String,
+        #[arg(long)]
+        config: PathBuf,
+    },
+    GetProductVersion {
+        product: String,
+        #[arg(long)]
+        version: u32,
+    },
+    GetProductVersionCount {
+        product: String,
+    },
+    GetProduct {
+        product: String,
+    },
+    GetProductEventIds {
+        product: String,
+    },
+    GetStats,
+    GetProductsByOwner {
+        owner: String,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    GetProductsByCategory {
+        category: String,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    GetProductsByTag {
+        tag: String,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+
+    // --- Actors ---
+    AddAuthorizedActor {
+        #[arg(long)]
+        owner: String,
+        #[arg(long)]
+        product: String,
+        #[arg(long)]
+        actor: String,
+        #[arg(long, value_enum, default_value_t = Role::Manager)]
+        role: Role,
+    },
+    AddTemporaryActor {
+        #[arg(long)]
+        owner: String,
+        #[arg(long)]
+        product: String,
+        #[arg(long)]
+        actor: String,
+        #[arg(long, value_enum)]
+        role: Role,
+        #[arg(long)]
+        valid_from: u64,
+        #[arg(long)]
+        valid_until: u64,
+    },
+    RemoveAuthorizedActor {
+        #[arg(long)]
+        owner: String,
+        #[arg(long)]
+        product: String,
+        #[arg(long)]
+        actor: String,
+    },
+    IsAuthorized {
+        #[arg(long)]
+        product: String,
+        #[arg(long)]
+        actor: String,
+    },
+    GetEffectiveActors {
+        product: String,
+    },
+    GetAuthorizedActors {
+        product: String,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    GetActorRole {
+        #[arg(long)]
+        product: String,
+        #[arg(long)]
+        actor: String,
+    },
+    SetRolePermissions {
+        #[arg(long)]
+        owner: String,
+        #[arg(long)]
+        product: String,
+        #[arg(long, value_enum)]
+        role: Role,
+        /// Comma-separated event types the role may record.
+        #[arg(long, value_delimiter = ',')]
+        event_types: Vec<String>,
+    },
+    GetRolePermissions {
+        #[arg(long)]
+        product: String,
+        #[arg(long, value_enum)]
+        role: Role,
+    },
+
+    // --- Transfers ---
+    TransferProduct {
+        #[arg(long)]
+        owner: String,
+        #[arg(long)]
+        product: String,
+        #[arg(long)]
+        new_owner: String,
+    },
+    ProposeTransfer {
+        #[arg(long)]
+        owner: String,
+        #[arg(long)]
+        product: String,
+        #[arg(long)]
+        new_owner: String,
+        #[arg(long)]
+        expires_at: u64,
+    },
+    AcceptTransfer {
+        #[arg(long)]
+        new_owner: String,
+        #[arg(long)]
+        product: String,
+    },
+    CancelTransfer {
+        #[arg(long)]
+        owner: String,
+        #[arg(long)]
+        product: String,
+    },
+    GetPendingTransfer {
+        product: String,
+    },
+
+    // --- Tracking Events ---
+    AddTrackingEvent {
+        #[arg(long)]
+        actor: String,
+        #[arg(long)]
+        product: String,
+        #[arg(long)]
+        event_type: String,
+        #[command(flatten)]
+        location: LocationArgs,
+        #[arg(long, default_value = ZERO_HASH)]
+        data_hash: String,
+        #[arg(long, default_value = "")]
+        note: String,
+        /// `key=value`, repeatable.
+        #[arg(long = "meta")]
+        metadata: Vec<String>,
+    },
+    /// Submit the batches printed by `chainlogistics-epcis import`, one
+    /// `add_tracking_events_batch` call each. Stops at the first batch that
+    /// fails; the batches before it stay recorded.
+    ImportEvents {
+        #[arg(long)]
+        actor: String,
+        batches: PathBuf,
+    },
+    GetEvent {
+        event_id: u64,
+    },
+    GetProductEvents {
+        product: String,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    GetEventsByType {
+        product: String,
+        #[arg(long)]
+        event_type: String,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    GetEventsByTimeRange {
+        product: String,
+        #[arg(long)]
+        start_time: u64,
+        #[arg(long)]
+        end_time: u64,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    GetFilteredEvents {
+        product: String,
+        #[arg(long, default_value = "")]
+        event_type: String,
+        #[arg(long, default_value_t = 0)]
+        start_time: u64,
+        #[arg(long, default_value_t = u64::MAX)]
+        end_time: u64,
+        #[arg(long, default_value = "")]
+        location: String,
+        #[arg(long, default_value = "")]
+        country: String,
+        #[arg(long, default_value = "")]
+        facility_id: String,
+        #[arg(long, default_value = "")]
+        geohash_prefix: String,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    GetProductEventsByActor {
+        product: String,
+        #[arg(long)]
+        actor: String,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    /// Everything one actor recorded, across all products.
+    GetEventsByActor {
+        actor: String,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    /// Continue an event listing from the `next_cursor` of its last page.
+    GetNextEvents {
+        cursor: String,
+        #[arg(long, default_value_t = 20)]
+        limit: u64,
+    },
+    VerifyEventChain {
+        product: String,
+    },
+    GetEventMerkleRoot {
+        product: String,
+    },
+    GetEventMerkleRootAt {
+        product: String,
+        #[arg(long)]
+        size: u64,
+    },
+    /// Extend one page of a product's storage; prints whether more pages
+    /// remain past `offset + limit`.
+    ExtendProductTtl {
+        #[arg(long)]
+        owner: String,
+        #[arg(long)]
+        product: String,
+        #[arg(long)]
+        ledgers: u32,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    /// Build an inclusion proof for one event against the product's current
+    /// Merkle root.
+    ProveEvent {
+        product: String,
+        #[arg(long)]
+        event_id: u64,
+    },
+    GetEventCount {
+        product: String,
+    },
+    GetEventCountByType {
+        product: String,
+        #[arg(long)]
+        event_type: String,
+    },
+
+    // --- Lineage ---
+    /// Split a product into the children described by a config file holding
+    /// a list of `ProductConfig`s.
+    SplitProduct {
+        #[arg(long)]
+        owner: String,
+        #[arg(long)]
+        parent: String,
+        #[arg(long)]
+        children: PathBuf,
+    },
+    MergeProducts {
+        #[arg(long)]
+        owner: String,
+        #[arg(long, value_delimiter = ',')]
+        parents: Vec<String>,
+        #[arg(long)]
+        child: PathBuf,
+    },
+    TransformProduct {
+        #[arg(long)]
+        owner: String,
+        #[arg(long)]
+        parent: String,
+        #[arg(long)]
+        child: PathBuf,
+    },
+    GetLineage {
+        product: String,
+        #[arg(long, default_value_t = 3)]
+        depth: u32,
+    },
+
+    // --- Recalls ---
+    /// Recall explicit products, a category or an origin; give exactly one.
+    InitiateRecall {
+        #[arg(long)]
+        initiator: String,
+        #[command(flatten)]
+        scope: RecallScopeArgs,
+        #[arg(long)]
+        reason: String,
+        #[arg(long, value_enum)]
+        severity: Severity,
+    },
+    GetRecall {
+        recall_id: u64,
+    },
+    GetRecallStatus {
+        product: String,
+    },
+
+    // --- Custody Lifecycle ---
+    SetCategoryLifecycle {
+        #[arg(long)]
+        admin: String,
+        #[arg(long)]
+        category: String,
+        /// JSON or TOML file with `initial` and `transitions`.
+        #[arg(long)]
+        lifecycle: PathBuf,
+    },
+    RemoveCategoryLifecycle {
+        #[arg(long)]
+        admin: String,
+        #[arg(long)]
+        category: String,
+    },
+    GetCategoryLifecycle {
+        category: String,
+    },
+    GetCurrentState {
+        product: String,
+    },
+
+    // --- Telemetry ---
+    SetTelemetryThresholds {
+        #[arg(long)]
+        owner: String,
+        #[arg(long)]
+        product: String,
+        #[arg(long, allow_hyphen_values = true)]
+        min_temperature: i32,
+        #[arg(long, allow_hyphen_values = true)]
+        max_temperature: i32,
+        #[arg(long)]
+        max_humidity: u32,
+        #[arg(long)]
+        max_shock: u32,
+    },
+    GetTelemetryThresholds {
+        product: String,
+    },
+    /// Sign a reading with the device's ed25519 secret key and submit it.
+    SubmitTelemetry {
+        #[command(flatten)]
+        device: DeviceKeyArgs,
+        #[arg(long)]
+        product: String,
+        /// Defaults to the ledger time.
+        #[arg(long)]
+        recorded_at: Option<u64>,
+        #[arg(long, allow_hyphen_values = true)]
+        temperature: i32,
+        #[arg(long, default_value_t = 0)]
+        humidity: u32,
+        #[arg(long, default_value_t = 0)]
+        shock: u32,
+        #[arg(long, default_value_t = 0.0, allow_hyphen_values = true)]
+        latitude: f64,
+        #[arg(long, default_value_t = 0.0, allow_hyphen_values = true)]
+        longitude: f64,
+    },
+    GetTelemetry {
+        event_id: u64,
+    },
+
+    // --- Device Registry ---
+    /// Print the public key for a device secret key.
+    DevicePublicKey {
+        #[command(flatten)]
+        device: DeviceKeyArgs,
+    },
+    RegisterDevice {
+        #[arg(long)]
+        sponsor: String,
+        #[arg(long)]
+        public_key: String,
+        #[arg(long, default_value = "")]
+        label: String,
+        /// Comma-separated product IDs the device may report on.
+        #[arg(long, value_delimiter = ',')]
+        scope: Vec<String>,
+    },
+    RotateDeviceKey {
+        #[arg(long)]
+        sponsor: String,
+        #[arg(long)]
+        old_key: String,
+        #[arg(long)]
+        new_key: String,
+    },
+    RevokeDevice {
+        #[arg(long)]
+        sponsor: String,
+        #[arg(long)]
+        public_key: String,
+    },
+    GetDevice {
+        public_key: String,
+    },
+    /// Sign a tracking event with the device's secret key and submit it.
+    AddDeviceEvent {
+        #[command(flatten)]
+        device: DeviceKeyArgs,
+        #[arg(long)]
+        product: String,
+        #[arg(long)]
+        event_type: String,
+        #[command(flatten)]
+        location: LocationArgs,
+        #[arg(long, default_value = ZERO_HASH)]
+        data_hash: String,
+        #[arg(long, default_value = "")]
+        note: String,
+        /// Defaults to the ledger time.
+        #[arg(long)]
+        recorded_at: Option<u64>,
+    },
+    GetEventDevice {
+        event_id: u64,
+    },
+
+    // --- Certification Registry ---
+    SetCertIssuer {
+        #[arg(long)]
+        admin: String,
+        #[arg(long)]
+        issuer: String,
+        #[arg(long)]
+        name: String,
+        #[arg(long, value_delimiter = ',')]
+        cert_types: Vec<String>,
+    },
+    RemoveCertIssuer {
+        #[arg(long)]
+        admin: String,
+        #[arg(long)]
+        issuer: String,
+    },
+    GetCertIssuer {
+        issuer: String,
+    },
+    AttestCertification {
+        #[arg(long)]
+        issuer: String,
+        #[arg(long)]
+        product: String,
+        #[arg(long)]
+        cert_type: String,
+        #[arg(long)]
+        doc_hash: String,
+        /// 0 for no expiry.
+        #[arg(long, default_value_t = 0)]
+        expires_at: u64,
+    },
+    RevokeCertification {
+        #[arg(long)]
+        issuer: String,
+        attestation_id: u64,
+    },
+    GetAttestation {
+        attestation_id: u64,
+    },
+    GetValidCertifications {
+        product: String,
+    },
+
+    // --- Emergency Controls ---
+    SetGuardian {
+        #[arg(long)]
+        admin: String,
+        #[arg(long)]
+        guardian: String,
+    },
+    RemoveGuardian {
+        #[arg(long)]
+        admin: String,
+    },
+    GetGuardian,
+    /// Pause one scope of writes. Callable by the admin or the guardian.
+    PauseScope {
+        #[arg(long)]
+        caller: String,
+        #[command(flatten)]
+        scope: ScopeArgs,
+        #[arg(long)]
+        reason: String,
+        /// Seconds until the pause lifts on its own; 0 for until unpaused.
+        #[arg(long, default_value_t = 0)]
+        duration: u64,
+    },
+    UnpauseScope {
+        #[arg(long)]
+        admin: String,
+        #[command(flatten)]
+        scope: ScopeArgs,
+    },
+    GetPause {
+        #[command(flatten)]
+        scope: ScopeArgs,
+    },
+    GetActivePauses,
+
+    // --- Admin Council ---
+    SetAdminCouncil {
+        #[arg(long)]
+        admin: String,
+        #[arg(long, value_delimiter = ',')]
+        signers: Vec<String>,
+        #[arg(long)]
+        threshold: u32,
+    },
+    GetAdminCouncil,
+    Upgrade {
+        #[arg(long)]
+        admin: String,
+        #[arg(long)]
+        wasm_hash: String,
+    },
+    ProposeAdminAction {
+        #[arg(long)]
+        signer: String,
+        #[command(subcommand)]
+        action: ActionCommand,
+    },
+    ApproveAdminProposal {
+        #[arg(long)]
+        signer: String,
+        proposal_id: u64,
+    },
+    ExecuteAdminProposal {
+        #[arg(long)]
+        signer: String,
+        proposal_id: u64,
+    },
+    CancelAdminProposal {
+        #[arg(long)]
+        signer: String,
+        proposal_id: u64,
+    },
+    GetAdminProposal {
+        proposal_id: u64,
+    },
+}
+
+/// Invoke the contract for `command` and render the result as JSON.
+pub fn run(command: Command, backend: &dyn Backend) -> Result<Value, CliError> {
+    let env = backend.env();
+    let addr = |s: &str| backend.address(s);
+    let text = |s: &str| soroban_sdk::String::from_str(env, s);
+    let sym = |s: &str| input::symbol(env, s);
+    let no_args = || soroban_sdk::Vec::<Val>::new(env);
+    let b = backend;
+
+    let value = match command {
+        // --- Admin ---
+        Command::Init { admin } => call::<()>(b, "init", (addr(&admin)?,))?.to_json(),
+        Command::GetAdmin => call::<Address>(b, "get_admin", no_args())?.to_json(),
+        Command::IsPaused => call::<bool>(b, "is_paused", no_args())?.to_json(),
+        Command::Pause { admin } => call::<()>(b, "pause", (addr(&admin)?,))?.to_json(),
+        Command::Unpause { admin } => call::<()>(b, "unpause", (addr(&admin)?,))?.to_json(),
+        Command::TransferAdmin { admin, new_admin } => {
+            call::<AdminHandover>(b, "transfer_admin", (addr(&admin)?, addr(&new_admin)?))?
+                .to_json()
+        }
+        Command::AcceptAdmin { new_admin } => {
+            call::<()>(b, "accept_admin", (addr(&new_admin)?,))?.to_json()
+        }
+        Command::CancelAdminTransfer { admin } => {
+            call::<()>(b, "cancel_admin_transfer", (addr(&admin)?,))?.to_json()
+        }
+        Command::GetAdminHandover => {
+            call::<AdminHandover>(b, "get_admin_handover", no_args())?.to_json()
+        }
+
+        // --- Products ---
+        Command::RegisterProduct { owner, config } => {
+            let config = load_file::<ProductConfigFile>(&config)?.to_contract(env)?;
+            call::<Product>(b, "register_product", (addr(&owner)?, config))?.to_json()
+        }
+        Command::DeactivateProduct {
+            owner,
+            product,
+            reason,
+        } => call::<()>(
+            b,
+            "deactivate_product",
+            (addr(&owner)?, text(&product), text(&reason)),
+        )?
+        .to_json(),
+        Command::ReactivateProduct { owner, product } => {
+            call::<()>(b, "reactivate_product", (addr(&owner)?, text(&product)))?.to_json()
+        }
+        Command::UpdateProductMetadata {
+            owner,
+            product,
+            config,
+        } => {
+            let metadata = load_file::<ProductMetadataFile>(&config)?.to_contract(env)?;
+            call::<ProductVersion>(
+                b,
+                "update_product_metadata",
+                (addr(&owner)?, text(&product), metadata),
+            )?
+            .to_json()
+        }
+        Command::GetProductVersion { product, version } => {
+            call::<ProductVersion>(b, "get_product_version", (text(&product), version))?.to_json()
+        }
+        Command::GetProductVersionCount { product } => {
+            call::<u32>(b, "get_product_version_count", (text(&product),))?.to_json()
+        }
+        Command::GetProduct { product } => {
+            call::<Product>(b, "get_product", (text(&product),))?.to_json()
+        }
+        Command::GetProductEventIds { product } => {
+            call::<soroban_sdk::Vec<u64>>(b, "get_product_event_ids", (text(&product),))?.to_json()
+        }
+        Command::GetStats => call::<ProductStats>(b, "get_stats", no_args())?.to_json(),
+        Command::GetProductsByOwner { owner, page } => call::<ProductPage>(
+            b,
+            "get_products_by_owner",
+            (addr(&owner)?, page.offset, page.limit),
+        )?
+        .to_json(),
+        Command::GetProductsByCategory { category, page } => call::<ProductPage>(
+            b,
+            "get_products_by_category",
+            (text(&category), page.offset, page.limit),
+        )?
+        .to_json(),
+        Command::GetProductsByTag { tag, page } => call::<ProductPage>(
+            b,
+            "get_products_by_tag",
+            (text(&tag), page.offset, page.limit),
+        )?
+        .to_json(),
+
+        // --- Actors ---
+        Command::AddAuthorizedActor {
+            owner,
+            product,
+            actor,
+            role,
+        } => call::<()>(
+            b,
+            "add_authorized_actor",
+            (
+                addr(&owner)?,
+                text(&product),
+                addr(&actor)?,
+                ActorRole::from(role),
+            ),
+        )?
+        .to_json(),
+        Command::AddTemporaryActor {
+            owner,
+            product,
+            actor,
+            role,
+            valid_from,
+            valid_until,
+        } => call::<()>(
+            b,
+            "add_temporary_actor",
+            (
+                addr(&owner)?,
+                text(&product),
+                addr(&actor)?,
+                ActorRole::from(role),
+                valid_from,
+                valid_until,
+            ),
+        )?
+        .to_json(),
+        Command::RemoveAuthorizedActor {
+            owner,
+            product,
+            actor,
+        } => call::<()>(
+            b,
+            "remove_authorized_actor",
+            (addr(&owner)?, text(&product), addr(&actor)?),
+        )?
+        .to_json(),
+        Command::IsAuthorized { product, actor } => {
+            call::<bool>(b, "is_authorized", (text(&product), addr(&actor)?))?.to_json()
+        }
+        Command::GetEffectiveActors { product } => {
+            call::<soroban_sdk::Vec<ActorGrant>>(b, "get_effective_actors", (text(&product),))?
+                .to_json()
+        }
+        Command::GetAuthorizedActors { product, page } => call::<ActorPage>(
+            b,
+            "get_authorized_actors",
+            (text(&product), page.offset, page.limit),
+        )?
+        .to_json(),
+        Command::GetActorRole { product, actor } => {
+            call::<ActorRole>(b, "get_actor_role", (text(&product), addr(&actor)?))?.to_json()
+        }
+        Command::SetRolePermissions {
+            owner,
+            product,
+            role,
+            event_types,
+        } => call::<()>(
+            b,
+            "set_role_permissions",
+            (
+                addr(&owner)?,
+                text(&product),
+                ActorRole::from(role),
+                input::symbols(env, &event_types)?,
+            ),
+        )?
+        .to_json(),
+        Command::GetRolePermissions { product, role } => call::<soroban_sdk::Vec<Symbol>>(
+            b,
+            "get_role_permissions",
+            (text(&product), ActorRole::from(role)),
+        )?
+        .to_json(),
+
+        // --- Transfers ---
+        Command::TransferProduct {
+            owner,
+            product,
+            new_owner,
+        } => call::<()>(
+            b,
+            "transfer_product",
+            (addr(&owner)?, text(&product), addr(&new_owner)?),
+        )?
+        .to_json(),
+        Command::ProposeTransfer {
+            owner,
+            product,
+            new_owner,
+            expires_at,
+        } => call::<PendingTransfer>(
+            b,
+            "propose_transfer",
+            (addr(&owner)?, text(&product), addr(&new_owner)?, expires_at),
+        )?
+        .to_json(),
+        Command::AcceptTransfer { new_owner, product } => {
+            call::<()>(b, "accept_transfer", (addr(&new_owner)?, text(&product)))?.to_json()
+        }
+        Command::CancelTransfer { owner, product } => {
+            call::<()>(b, "cancel_transfer", (addr(&owner)?, text(&product)))?.to_json()
+        }
+        Command::GetPendingTransfer { product } => {
+            call::<PendingTransfer>(b, "get_pending_transfer", (text(&product),))?.to_json()
+        }
+
+        // --- Tracking Events ---
+        Command::AddTrackingEvent {
+            actor,
+            product,
+            event_type,
+            location,
+            data_hash,
+            note,
+            metadata,
+        } => {
+            let event_id = call::<u64>(
+                b,
+                "add_tracking_event",
+                (
+                    addr(&actor)?,
+                    text(&product),
+                    sym(&event_type)?,
+                    location.to_contract(env)?,
+                    input::hash(env, &data_hash)?,
+                    text(&note),
+                    input::metadata(env, &metadata)?,
+                ),
+            )?;
+            json!({ "event_id": event_id })
+        }
+        Command::ImportEvents { actor, batches } => {
+            let actor = addr(&actor)?;
+            let mut event_ids = Vec::new();
+            for batch in load_file::<Vec<Vec<ImportedEvent>>>(&batches)? {
+                let mut inputs = soroban_sdk::Vec::new(env);
+                for event in &batch {
+                    inputs.push_back(input::tracking_event_input(env, event)?);
+                }
+                let ids = call::<soroban_sdk::Vec<u64>>(
+                    b,
+                    "add_tracking_events_batch",
+                    (actor.clone(), inputs),
+                )?;
+                event_ids.extend(ids.iter());
+            }
+            json!({ "event_ids": event_ids })
+        }
+        Command::GetEvent { event_id } => {
+            call::<TrackingEvent>(b, "get_event", (event_id,))?.to_json()
+        }
+        Command::GetProductEvents { product, page } => call::<TrackingEventPage>(
+            b,
+            "get_product_events",
+            (text(&product), page.offset, page.limit),
+        )?
+        .to_json(),
+        Command::GetEventsByType {
+            product,
+            event_type,
+            page,
+        } => call::<TrackingEventPage>(
+            b,
+            "get_events_by_type",
+            (text(&product), sym(&event_type)?, page.offset, page.limit),
+        )?
+        .to_json(),
+        Command::GetEventsByTimeRange {
+            product,
+            start_time,
+            end_time,
+            page,
+        } => call::<TrackingEventPage>(
+            b,
+            "get_events_by_time_range",
+            (
+                text(&product),
+                start_time,
+                end_time,
+                page.offset,
+                page.limit,
+            ),
+        )?
+        .to_json(),
+        Command::GetFilteredEvents {
+            product,
+            event_type,
+            start_time,
+            end_time,
+            location,
+            country,
+            facility_id,
+            geohash_prefix,
+            page,
+        } => {
+            let filter = TrackingEventFilter {
+                event_type: sym(&event_type)?,
+                start_time,
+                end_time,
+                location: text(&location),
+                country: text(&country),
+                facility_id: text(&facility_id),
+                geohash_prefix: text(&geohash_prefix),
+            };
+            call::<TrackingEventPage>(
+                b,
+                "get_filtered_events",
+                (text(&product), filter, page.offset, page.limit),
+            )?
+            .to_json()
+        }
+        Command::GetProductEventsByActor {
+            product,
+            actor,
+            page,
+        } => call::<TrackingEventPage>(
+            b,
+            "get_product_events_by_actor",
+            (text(&product), addr(&actor)?, page.offset, page.limit),
+        )?
+        .to_json(),
+        Command::GetEventsByActor { actor, page } => call::<TrackingEventPage>(
+            b,
+            "get_events_by_actor",
+            (addr(&actor)?, page.offset, page.limit),
+        )?
+        .to_json(),
+        Command::GetNextEvents { cursor, limit } => {
+            call::<TrackingEventPage>(b, "get_next_events", (input::bytes(env, &cursor)?, limit))?
+                .to_json()
+        }
+        Command::VerifyEventChain { product } => {
+            call::<EventChainReport>(b, "verify_event_chain", (text(&product),))?.to_json()
+        }
+        Command::GetEventMerkleRoot { product } => {
+            call::<EventMerkleRoot>(b, "get_event_merkle_root", (text(&product),))?.to_json()
+        }
+        Command::GetEventMerkleRootAt { product, size } => {
+            call::<BytesN<32>>(b, "get_event_merkle_root_at", (text(&product), size))?.to_json()
+        }
+        Command::ExtendProductTtl {
+            owner,
+            product,
+            ledgers,
+            page,
+        } => call::<bool>(
+            b,
+            "extend_product_ttl",
+            (
+                addr(&owner)?,
+                text(&product),
+                ledgers,
+                page.offset,
+                page.limit,
+            ),
+        )?
+        .to_json(),
+        Command::ProveEvent { product, event_id } => {
+            prove_event(backend, text(&product), event_id)?
+        }
+        Command::GetEventCount { product } => {
+            call::<u64>(b, "get_event_count", (text(&product),))?.to_json()
+        }
+        Command::GetEventCountByType {
+            product,
+            event_type,
+        } => call::<u64>(
+            b,
+            "get_event_count_by_type",
+            (text(&product), sym(&event_type)?),
+        )?
+        .to_json(),
+
+        // --- Lineage ---
+        Command::SplitProduct {
+            owner,
+            parent,
+            children,
+        } => {
+            let mut configs = soroban_sdk::Vec::new(env);
+            for config in load_file::<ProductConfigs>(&children)?.into_vec() {
+                configs.push_back(config.to_contract(env)?);
+            }
+            call::<soroban_sdk::Vec<Product>>(
+                b,
+                "split_product",
+                (addr(&owner)?, text(&parent), configs),
+            )?
+            .to_json()
+        }
+        Command::MergeProducts {
+            owner,
+            parents,
+            child,
+        } => {
+            let child = load_file::<ProductConfigFile>(&child)?.to_contract(env)?;
+            call::<Product>(
+                b,
+                "merge_products",
+                (addr(&owner)?, input::strings(env, &parents), child),
+            )?
+            .to_json()
+        }
+        Command::TransformProduct {
+            owner,
+            parent,
+            child,
+        } => {
+            let child = load_file::<ProductConfigFile>(&child)?.to_contract(env)?;
+            call::<Product>(
+                b,
+                "transform_product",
+                (addr(&owner)?, text(&parent), child),
+            )?
+            .to_json()
+        }
+        Command::GetLineage { product, depth } => {
+            call::<Lineage>(b, "get_lineage", (text(&product), depth))?.to_json()
+        }
+
+        // --- Recalls ---
+        Command::InitiateRecall {
+            initiator,
+            scope,
+            reason,
+            severity,
+        } => call::<Recall>(
+            b,
+            "initiate_recall",
+            (
+                addr(&initiator)?,
+                scope.to_contract(env),
+                text(&reason),
+                RecallSeverity::from(severity),
+            ),
+        )?
+        .to_json(),
+        Command::GetRecall { recall_id } => {
+            call::<Recall>(b, "get_recall", (recall_id,))?.to_json()
+        }
+        Command::GetRecallStatus { product } => {
+            call::<RecallStatus>(b, "get_recall_status", (text(&product),))?.to_json()
+        }
+
+        // --- Custody Lifecycle ---
+        Command::SetCategoryLifecycle {
+            admin,
+            category,
+            lifecycle,
+        } => {
+            let lifecycle = load_file::<LifecycleFile>(&lifecycle)?.to_contract(env)?;
+            call::<()>(
+                b,
+                "set_category_lifecycle",
+                (addr(&admin)?, text(&category), lifecycle),
+            )?
+            .to_json()
+        }
+        Command::RemoveCategoryLifecycle { admin, category } => call::<()>(
+            b,
+            "remove_category_lifecycle",
+            (addr(&admin)?, text(&category)),
+        )?
+        .to_json(),
+        Command::GetCategoryLifecycle { category } => {
+            call::<Lifecycle>(b, "get_category_lifecycle", (text(&category),))?.to_json()
+        }
+        Command::GetCurrentState { product } => {
+            call::<Symbol>(b, "get_current_state", (text(&product),))?.to_json()
+        }
+
+        // --- Telemetry ---
+        Command::SetTelemetryThresholds {
+            owner,
+            product,
+            min_temperature,
+            max_temperature,
+            max_humidity,
+            max_shock,
+        } => {
+            let thresholds = TelemetryThresholds {
+                min_temperature,
+                max_temperature,
+                max_humidity,
+                max_shock,
+            };
+            call::<()>(
+                b,
+                "set_telemetry_thresholds",
+                (addr(&owner)?, text(&product), thresholds),
+            )?
+            .to_json()
+        }
+        Command::GetTelemetryThresholds { product } => {
+            call::<TelemetryThresholds>(b, "get_telemetry_thresholds", (text(&product),))?.to_json()
+        }
+        Command::SubmitTelemetry {
+            device,
+            product,
+            recorded_at,
+            temperature,
+            humidity,
+            shock,
+            latitude,
+            longitude,
+        } => {
+            let device = device.signing_key()?;
+            let product_id = text(&product);
+            let reading = TelemetryReading {
+                recorded_at: recorded_at.unwrap_or_else(|| backend.timestamp()),
+                temperature,
+                humidity,
+                shock,
+                latitude: input::micro_degrees(latitude)?,
+                longitude: input::micro_degrees(longitude)?,
+            };
+            let body = (product_id.clone(), reading.clone());
+            let signature = sign(backend, &device, body.into_val(env));
+            let event_id = call::<u64>(
+                b,
+                "submit_telemetry",
+                (public_key(env, &device), product_id, reading, signature),
+            )?;
+            json!({ "event_id": event_id })
+        }
+        Command::GetTelemetry { event_id } => {
+            call::<TelemetryReading>(b, "get_telemetry", (event_id,))?.to_json()
+        }
+
+        // --- Device Registry ---
+        Command::DevicePublicKey { device } => public_key(env, &device.signing_key()?).to_json(),
+        Command::RegisterDevice {
+            sponsor,
+            public_key,
+            label,
+            scope,
+        } => call::<Device>(
+            b,
+            "register_device",
+            (
+                addr(&sponsor)?,
+                input::hash(env, &public_key)?,
+                text(&label),
+                input::strings(env, &scope),
+            ),
+        )?
+        .to_json(),
+        Command::RotateDeviceKey {
+            sponsor,
+            old_key,
+            new_key,
+        } => call::<Device>(
+            b,
+            "rotate_device_key",
+            (
+                addr(&sponsor)?,
+                input::hash(env, &old_key)?,
+                input::hash(env, &new_key)?,
+            ),
+        )?
+        .to_json(),
+        Command::RevokeDevice {
+            sponsor,
+            public_key,
+        } => call::<()>(
+            b,
+            "revoke_device",
+            (addr(&sponsor)?, input::hash(env, &public_key)?),
+        )?
+        .to_json(),
+        Command::GetDevice { public_key } => {
+            call::<Device>(b, "get_device", (input::hash(env, &public_key)?,))?.to_json()
+        }
+        Command::AddDeviceEvent {
+            device,
+            product,
+            event_type,
+            location,
+            data_hash,
+            note,
+            recorded_at,
+        } => {
+            let device = device.signing_key()?;
+            let product_id = text(&product);
+            let event_type = sym(&event_type)?;
+            let location = location.to_contract(env)?;
+            let data_hash = input::hash(env, &data_hash)?;
+            let note = text(&note);
+            let recorded_at = recorded_at.unwrap_or_else(|| backend.timestamp());
+            let body = (
+                product_id.clone(),
+                event_type.clone(),
+                location.clone(),
+                data_hash.clone(),
+                note.clone(),
+                recorded_at,
+            );
+            let signature = sign(backend, &device, body.into_val(env));
+            let event_id = call::<u64>(
+                b,
+                "add_device_event",
+                (
+                    public_key(env, &device),
+                    product_id,
+                    event_type,
+                    location,
+                    data_hash,
+                    note,
+                    recorded_at,
+                    signature,
+                ),
+            )?;
+            json!({ "event_id": event_id })
+        }
+        Command::GetEventDevice { event_id } => {
+            call::<BytesN<32>>(b, "get_event_device", (event_id,))?.to_json()
+        }
+
+        // --- Certification Registry ---
+        Command::SetCertIssuer {
+            admin,
+            issuer,
+            name,
+            cert_types,
+        } => call::<()>(
+            b,
+            "set_cert_issuer",
+            (
+                addr(&admin)?,
+                addr(&issuer)?,
+                text(&name),
+                input::symbols(env, &cert_types)?,
+            ),
+        )?
+        .to_json(),
+        Command::RemoveCertIssuer { admin, issuer } => {
+            call::<()>(b, "remove_cert_issuer", (addr(&admin)?, addr(&issuer)?))?.to_json()
+        }
+        Command::GetCertIssuer { issuer } => {
+            call::<CertIssuer>(b, "get_cert_issuer", (addr(&issuer)?,))?.to_json()
+        }
+        Command::AttestCertification {
+            issuer,
+            product,
+            cert_type,
+            doc_hash,
+            expires_at,
+        } => {
+            let attestation_id = call::<u64>(
+                b,
+                "attest_certification",
+                (
+                    addr(&issuer)?,
+                    text(&product),
+                    sym(&cert_type)?,
+                    input::hash(env, &doc_hash)?,
+                    expires_at,
+                ),
+            )?;
+            json!({ "attestation_id": attestation_id })
+        }
+        Command::RevokeCertification {
+            issuer,
+            attestation_id,
+        } => call::<()>(b, "revoke_certification", (addr(&issuer)?, attestation_id))?.to_json(),
+        Command::GetAttestation { attestation_id } => {
+            call::<Attestation>(b, "get_attestation", (attestation_id,))?.to_json()
+        }
+        Command::GetValidCertifications { product } => {
+            call::<soroban_sdk::Vec<Attestation>>(b, "get_valid_certifications", (text(&product),))?
+                .to_json()
+        }
+
+        // --- Emergency Controls ---
+        Command::SetGuardian { admin, guardian } => {
+            call::<()>(b, "set_guardian", (addr(&admin)?, addr(&guardian)?))?.to_json()
+        }
+        Command::RemoveGuardian { admin } => {
+            call::<()>(b, "remove_guardian", (addr(&admin)?,))?.to_json()
+        }
+        Command::GetGuardian => call::<Option<Address>>(b, "get_guardian", no_args())?.to_json(),
+        Command::PauseScope {
+            caller,
+            scope,
+            reason,
+            duration,
+        } => call::<PauseRecord>(
+            b,
+            "pause_scope",
+            (
+                addr(&caller)?,
+                scope.to_contract(env)?,
+                text(&reason),
+                duration,
+            ),
+        )?
+        .to_json(),
+        Command::UnpauseScope { admin, scope } => {
+            call::<()>(b, "unpause_scope", (addr(&admin)?, scope.to_contract(env)?))?.to_json()
+        }
+        Command::GetPause { scope } => {
+            call::<PauseRecord>(b, "get_pause", (scope.to_contract(env)?,))?.to_json()
+        }
+        Command::GetActivePauses => {
+            call::<soroban_sdk::Vec<PauseRecord>>(b, "get_active_pauses", no_args())?.to_json()
+        }
+
+        // --- Admin Council ---
+        Command::SetAdminCouncil {
+            admin,
+            signers,
+            threshold,
+        } => {
+            let mut addresses = soroban_sdk::Vec::new(env);
+            for signer in &signers {
+                addresses.push_back(addr(signer)?);
+            }
+            call::<()>(
+                b,
+                "set_admin_council",
+                (addr(&admin)?, addresses, threshold),
+            )?
+            .to_json()
+        }
+        Command::GetAdminCouncil => {
+            call::<AdminCouncil>(b, "get_admin_council", no_args())?.to_json()
+        }
+        Command::Upgrade { admin, wasm_hash } => {
+            call::<()>(b, "upgrade", (addr(&admin)?, input::hash(env, &wasm_hash)?))?.to_json()
+        }
+        Command::ProposeAdminAction { signer, action } => {
+            let action = action.to_contract(backend)?;
+            call::<AdminProposal>(b, "propose_admin_action", (addr(&signer)?, action))?.to_json()
+        }
+        Command::ApproveAdminProposal {
+            signer,
+            proposal_id,
+        } => call::<AdminProposal>(b, "approve_admin_proposal", (addr(&signer)?, proposal_id))?
+            .to_json(),
+        Command::ExecuteAdminProposal {
+            signer,
+            proposal_id,
+        } => call::<AdminProposal>(b, "execute_admin_proposal", (addr(&signer)?, proposal_id))?
+            .to_json(),
+        Command::CancelAdminProposal {
+            signer,
+            proposal_id,
+        } => call::<()>(b, "cancel_admin_proposal", (addr(&signer)?, proposal_id))?.to_json(),
+        Command::GetAdminProposal { proposal_id } => {
+            call::<AdminProposal>(b, "get_admin_proposal", (proposal_id,))?.to_json()
+        }
+    };
+    Ok(value)
+}
+
+// ─── Event proofs ────────────────────────────────────────────────────────────
+
+/// Rebuild the product's event tree from its events and prove `event_id`.
+fn prove_event(
+    backend: &dyn Backend,
+    product_id: soroban_sdk::String,
+    event_id: u64,
+) -> Result<Value, CliError> {
+    let env = backend.env();
+    let ids = call::<soroban_sdk::Vec<u64>>(backend, "get_product_event_ids", (product_id,))?;
+    let index = ids
+        .first_index_of(event_id)
+        .ok_or_else(|| CliError::Input(format!("event {event_id} is not on this product")))?;
+
+    let mut hashes = Vec::new();
+    for eid in ids.iter() {
+        let event = call::<TrackingEvent>(backend, "get_event", (eid,))?;
+        hashes.push(env.crypto().sha256(&event.to_xdr(env)).to_array());
+    }
+    let event_hash = hashes[index as usize];
+    let tree = MerkleTree::new(hashes);
+    let proof = tree
+        .proof(index as u64)
+        .ok_or_else(|| CliError::Host("proof index out of range".into()))?;
+
+    Ok(json!({
+        "event_id": event_id,
+        "event_hash": to_hex(&event_hash),
+        "root": to_hex(&tree.root()),
+        "proof": serde_json::to_value(&proof).map_err(|e| CliError::Host(e.to_string()))?,
+    }))
+}
+
+// ─── Device signing ──────────────────────────────────────────────────────────
+
+fn public_key(env: &Env, device: &SigningKey) -> BytesN<32> {
+    BytesN::from_array(env, &device.verifying_key().to_bytes())
+}
+
+/// Sign a device submission the way the contract verifies it: the XDR of
+/// `(network_id, contract, body)`.
+fn sign(backend: &dyn Backend, device: &SigningKey, body: Val) -> BytesN<64> {
+    let env = backend.env();
+    let domain = (backend.network_id(), backend.contract_id().clone(), body);
+    let message: Vec<u8> = domain.to_xdr(env).iter().collect();
+    BytesN::from_array(env, &device.sign(&message).to_bytes())
+}
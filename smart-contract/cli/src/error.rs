@@ -0,0 +1,72 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum CliError {
+    /// The contract returned one of its own errors.
+    Contract(chainlogistics::Error),
+    /// The host rejected the invocation (auth, budget, panic, bad value).
+    Host(String),
+    /// A command-line argument or input file could not be used.
+    Input(String),
+    /// Reading or writing sandbox state failed.
+    Io(String),
+    /// The RPC server could not be reached or refused a request.
+    Rpc(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Contract(err) => write!(f, "contract error: {err:?} ({})", *err as u32),
+            CliError::Host(msg) => write!(f, "host error: {msg}"),
+            CliError::Input(msg) => write!(f, "invalid input: {msg}"),
+            CliError::Io(msg) => write!(f, "sandbox state: {msg}"),
+            CliError::Rpc(msg) => write!(f, "rpc: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<chainlogistics::Error> for CliError {
+    fn from(err: chainlogistics::Error) -> Self {
+        CliError::Contract(err)
+    }
+}
+
+impl From<soroban_sdk::Error> for CliError {
+    fn from(err: soroban_sdk::Error) -> Self {
+        CliError::Host(format!("{err:?}"))
+    }
+}
+
+impl From<std::io::Error> for CliError {
+    fn from(err: std::io::Error) -> Self {
+        CliError::Io(err.to_string())
+    }
+}
+
+/// Unwrap the nested result of a `try_*` host invocation.
+#[cfg(any(test, feature = "sandbox"))]
+pub fn invoke<T, C, E, I>(result: Result<Result<T, C>, Result<E, I>>) -> Result<T, CliError>
+where
+    C: fmt::Debug,
+    E: Into<CliError>,
+    I: fmt::Debug,
+{
+    match result {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(conversion)) => Err(CliError::Host(format!("{conversion:?}"))),
+        Err(Ok(err)) => Err(err.into()),
+        Err(Err(invoke)) => Err(CliError::Host(format!("{invoke:?}"))),
+    }
+}
+
+/// The contract error named in a host diagnostic such as
+/// `HostError: Error(Contract, #7)`, as RPC simulation reports failures.
+pub fn contract_error(message: &str) -> Option<chainlogistics::Error> {
+    let (_, rest) = message.split_once("Error(Contract, #")?;
+    let end = rest.find(|c: char| !c.is_ascii_digit())?;
+    let code = rest[..end].parse().ok()?;
+    chainlogistics::Error::try_from(soroban_sdk::Error::from_contract_error(code)).ok()
+}
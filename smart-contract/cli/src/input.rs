@@ -0,0 +1,345 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chainlogistics::{
+    Coordinates, Lifecycle, LifecycleTransition, Location, ProductConfig, ProductMetadata,
+    TrackingEventInput,
+};
+use chainlogistics_epcis::ImportedEvent;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use soroban_sdk::{Bytes, BytesN, Env, Map, String, Symbol, Vec};
+
+use crate::error::CliError;
+
+// ─── Files ───────────────────────────────────────────────────────────────────
+
+/// Read a JSON or TOML file, chosen by extension.
+pub fn load_file<T: DeserializeOwned>(path: &Path) -> Result<T, CliError> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| CliError::Input(format!("{}: {e}", path.display())))?;
+    let parsed = match path.extension().and_then(|e| e.to_str()) {
+        Some("toml") => toml::from_str(&text).map_err(|e| e.to_string()),
+        _ => serde_json::from_str(&text).map_err(|e| e.to_string()),
+    };
+    parsed.map_err(|e| CliError::Input(format!("{}: {e}", path.display())))
+}
+
+/// `Location` as written in config files. Coordinates are decimal degrees.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LocationFile {
+    pub name: std::string::String,
+    pub facility_id: std::string::String,
+    pub country: std::string::String,
+    pub geohash: std::string::String,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+impl LocationFile {
+    pub fn to_contract(&self, env: &Env) -> Result<Location, CliError> {
+        let mut coordinates = Vec::new(env);
+        match (self.latitude, self.longitude) {
+            (Some(latitude), Some(longitude)) => coordinates.push_back(Coordinates {
+                latitude: micro_degrees(latitude)?,
+                longitude: micro_degrees(longitude)?,
+            }),
+            (None, None) => {}
+            _ => {
+                return Err(CliError::Input(
+                    "latitude and longitude must be given together".into(),
+                ))
+            }
+        }
+        Ok(Location {
+            name: String::from_str(env, &self.name),
+            facility_id: String::from_str(env, &self.facility_id),
+            country: String::from_str(env, &self.country),
+            geohash: String::from_str(env, &self.geohash),
+            coordinates,
+        })
+    }
+}
+
+/// `ProductConfig` as written in config files. Hashes are hex strings.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProductConfigFile {
+    pub id: std::string::String,
+    pub name: std::string::String,
+    pub description: std::string::String,
+    pub origin: LocationFile,
+    pub category: std::string::String,
+    pub tags: std::vec::Vec<std::string::String>,
+    pub certifications: std::vec::Vec<std::string::String>,
+    pub media_hashes: std::vec::Vec<std::string::String>,
+    pub custom: BTreeMap<std::string::String, std::string::String>,
+}
+
+impl ProductConfigFile {
+    pub fn to_contract(&self, env: &Env) -> Result<ProductConfig, CliError> {
+        let mut custom = Map::new(env);
+        for (key, value) in &self.custom {
+            custom.set(symbol(env, key)?, String::from_str(env, value));
+        }
+        Ok(ProductConfig {
+            id: String::from_str(env, &self.id),
+            name: String::from_str(env, &self.name),
+            description: String::from_str(env, &self.description),
+            origin: self.origin.to_contract(env)?,
+            category: String::from_str(env, &self.category),
+            tags: strings(env, &self.tags),
+            certifications: hashes(env, &self.certifications)?,
+            media_hashes: hashes(env, &self.media_hashes)?,
+            custom,
+        })
+    }
+}
+
+/// `ProductMetadata` as written in config files. Hashes are hex strings.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProductMetadataFile {
+    pub name: std::string::String,
+    pub description: std::string::String,
+    pub tags: std::vec::Vec<std::string::String>,
+    pub media_hashes: std::vec::Vec<std::string::String>,
+    pub custom: BTreeMap<std::string::String, std::string::String>,
+}
+
+impl ProductMetadataFile {
+    pub fn to_contract(&self, env: &Env) -> Result<ProductMetadata, CliError> {
+        let mut custom = Map::new(env);
+        for (key, value) in &self.custom {
+            custom.set(symbol(env, key)?, String::from_str(env, value));
+        }
+        Ok(ProductMetadata {
+            name: String::from_str(env, &self.name),
+            description: String::from_str(env, &self.description),
+            tags: strings(env, &self.tags),
+            media_hashes: hashes(env, &self.media_hashes)?,
+            custom,
+        })
+    }
+}
+
+/// Either a single config or a list, so one file can feed `split-product`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub enum ProductConfigs {
+    Many(std::vec::Vec<ProductConfigFile>),
+    One(ProductConfigFile),
+}
+
+impl ProductConfigs {
+    pub fn into_vec(self) -> std::vec::Vec<ProductConfigFile> {
+        match self {
+            ProductConfigs::Many(configs) => configs,
+            ProductConfigs::One(config) => vec![config],
+        }
+    }
+}
+
+/// An event printed by `chainlogistics-epcis import`, as a batch input.
+/// Coordinates are already in micro-degrees.
+pub fn tracking_event_input(
+    env: &Env,
+    event: &ImportedEvent,
+) -> Result<TrackingEventInput, CliError> {
+    let location = &event.location;
+    let mut coordinates = Vec::new(env);
+    if let Some(c) = location.coordinates {
+        coordinates.push_back(Coordinates {
+            latitude: c.latitude,
+            longitude: c.longitude,
+        });
+    }
+    let mut metadata = Map::new(env);
+    for (key, value) in &event.metadata {
+        metadata.set(symbol(env, key)?, String::from_str(env, value));
+    }
+    Ok(TrackingEventInput {
+        product_id: String::from_str(env, &event.product_id),
+        event_type: symbol(env, &event.event_type)?,
+        location: Location {
+            name: String::from_str(env, &location.name),
+            facility_id: String::from_str(env, &location.facility_id),
+            country: String::from_str(env, &location.country),
+            geohash: String::from_str(env, &location.geohash),
+            coordinates,
+        },
+        data_hash: hash(env, &event.data_hash)?,
+        note: String::from_str(env, &event.note),
+        metadata,
+    })
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransitionFile {
+    pub from: std::string::String,
+    pub to: std::string::String,
+}
+
+/// `Lifecycle` as written in config files.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LifecycleFile {
+    pub initial: std::vec::Vec<std::string::String>,
+    pub transitions: std::vec::Vec<TransitionFile>,
+}
+
+impl LifecycleFile {
+    pub fn to_contract(&self, env: &Env) -> Result<Lifecycle, CliError> {
+        let mut transitions = Vec::new(env);
+        for t in &self.transitions {
+            transitions.push_back(LifecycleTransition {
+                from: symbol(env, &t.from)?,
+                to: symbol(env, &t.to)?,
+            });
+        }
+        Ok(Lifecycle {
+            initial: symbols(env, &self.initial)?,
+            transitions,
+        })
+    }
+}
+
+// ─── Scalar arguments ────────────────────────────────────────────────────────
+
+/// A contract `Symbol`: up to 32 characters from `[a-zA-Z0-9_]`. Checked
+/// here because the SDK panics on invalid symbols.
+pub fn symbol(env: &Env, s: &str) -> Result<Symbol, CliError> {
+    let valid = s.len() <= 32 && s.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_');
+    if !valid {
+        return Err(CliError::Input(format!("`{s}` is not a valid symbol")));
+    }
+    Ok(Symbol::new(env, s))
+}
+
+pub fn symbols(env: &Env, items: &[std::string::String]) -> Result<Vec<Symbol>, CliError> {
+    let mut out = Vec::new(env);
+    for item in items {
+        out.push_back(symbol(env, item)?);
+    }
+    Ok(out)
+}
+
+pub fn strings(env: &Env, items: &[std::string::String]) -> Vec<String> {
+    let mut out = Vec::new(env);
+    for item in items {
+        out.push_back(String::from_str(env, item));
+    }
+    out
+}
+
+pub fn hex_bytes<const N: usize>(s: &str) -> Result<[u8; N], CliError> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    if s.len() != N * 2 {
+        return Err(CliError::Input(format!(
+            "expected {} hex characters, got {}",
+            N * 2,
+            s.len()
+        )));
+    }
+    let mut out = [0u8; N];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16)
+            .map_err(|_| CliError::Input(format!("`{s}` is not valid hex")))?;
+    }
+    Ok(out)
+}
+
+pub fn hash(env: &Env, s: &str) -> Result<BytesN<32>, CliError> {
+    Ok(BytesN::from_array(env, &hex_bytes::<32>(s)?))
+}
+
+/// Hex of any even length, such as an event page's `next_cursor`.
+pub fn bytes(env: &Env, s: &str) -> Result<Bytes, CliError> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    let invalid = || CliError::Input(format!("`{s}` is not valid hex"));
+    if !s.len().is_multiple_of(2) || !s.is_ascii() {
+        return Err(invalid());
+    }
+    let mut out = Bytes::new(env);
+    for i in (0..s.len()).step_by(2) {
+        out.push_back(u8::from_str_radix(&s[i..i + 2], 16).map_err(|_| invalid())?);
+    }
+    Ok(out)
+}
+
+fn hashes(env: &Env, items: &[std::string::String]) -> Result<Vec<BytesN<32>>, CliError> {
+    let mut out = Vec::new(env);
+    for item in items {
+        out.push_back(hash(env, item)?);
+    }
+    Ok(out)
+}
+
+/// Parse `key=value` metadata pairs.
+pub fn metadata(env: &Env, pairs: &[std::string::String]) -> Result<Map<Symbol, String>, CliError> {
+    let mut out = Map::new(env);
+    for pair in pairs {
+        let (key, value) = pair
+            .split_once('=')
+            .ok_or_else(|| CliError::Input(format!("metadata `{pair}` is not key=value")))?;
+        out.set(symbol(env, key)?, String::from_str(env, value));
+    }
+    Ok(out)
+}
+
+/// Stellar account (`G…`) or contract (`C…`) strkey.
+pub fn is_strkey(s: &str) -> bool {
+    s.len() == 56
+        && (s.starts_with('G') || s.starts_with('C'))
+        && s.bytes()
+            .all(|b| b.is_ascii_uppercase() || (b'2'..=b'7').contains(&b))
+}
+
+/// Decimal degrees to the contract's micro-degrees.
+pub fn micro_degrees(degrees: f64) -> Result<i32, CliError> {
+    let micro = (degrees * 1_000_000.0).round();
+    if !micro.is_finite() || micro.abs() > i32::MAX as f64 {
+        return Err(CliError::Input(format!("{degrees} is not a coordinate")));
+    }
+    Ok(micro as i32)
+}
+
+// ─── Secret keys ─────────────────────────────────────────────────────────────
+
+/// A secret read from `file`, or from the environment variable `var` when no
+/// file is given. Secrets are never taken as arguments, where they would be
+/// left in shell history and visible to other users in the process list.
+fn read_secret(file: Option<&Path>, var: &str) -> Result<Option<std::string::String>, CliError> {
+    match file {
+        Some(path) => std::fs::read_to_string(path)
+            .map(|secret| Some(secret.trim().to_string()))
+            .map_err(|e| CliError::Input(format!("{}: {e}", path.display()))),
+        None => Ok(std::env::var(var)
+            .ok()
+            .map(|secret| secret.trim().to_string())),
+    }
+}
+
+/// An account's `S…` secret seed, if one was supplied.
+pub fn secret_seed(file: Option<&Path>, var: &str) -> Result<Option<[u8; 32]>, CliError> {
+    read_secret(file, var)?
+        .map(|seed| {
+            stellar_strkey::ed25519::PrivateKey::from_string(&seed)
+                .map(|key| key.0)
+                .map_err(|_| CliError::Input("source key is not an `S…` secret seed".into()))
+        })
+        .transpose()
+}
+
+/// A device's ed25519 secret key, as hex.
+pub fn device_key(file: Option<&Path>, var: &str) -> Result<[u8; 32], CliError> {
+    let secret = read_secret(file, var)?.ok_or_else(|| {
+        CliError::Input(format!(
+            "pass --secret-key-file or set ${var} to the device key"
+        ))
+    })?;
+    hex_bytes::<32>(&secret)
+}
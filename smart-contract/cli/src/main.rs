@@ -0,0 +1,140 @@
+//! `chainlogistics`: command-line client for the ChainLogistics contract.
+//!
+//! Every contract entry point is a subcommand. With `--rpc-url` and
+//! `--contract`, commands run against a deployed contract through Soroban
+//! RPC: reads are simulated, and writes are signed by the `--source-key-file`
+//! account and submitted. Builds with the `sandbox` feature can instead run
+//! against a local ledger stored in `--state` (default `.chainlogistics/`),
+//! with the contract executing natively and authorization mocked, so
+//! operators can script and rehearse flows without a network.
+
+mod backend;
+mod commands;
+mod error;
+mod input;
+mod render;
+mod rpc;
+#[cfg(any(test, feature = "sandbox"))]
+mod sandbox;
+
+#[cfg(test)]
+mod test;
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use serde_json::Value;
+
+use crate::commands::Command;
+use crate::error::CliError;
+use crate::render::OutputFormat;
+use crate::rpc::{NetworkArgs, Rpc};
+
+#[derive(Parser, Debug)]
+#[command(name = "chainlogistics", version, about)]
+struct Cli {
+    #[command(flatten)]
+    network: NetworkArgs,
+    /// Directory holding the sandbox ledger and identities.
+    #[arg(long, global = true, default_value = ".chainlogistics")]
+    state: PathBuf,
+    /// Sandbox ledger timestamp to run at, in Unix seconds. Defaults to now.
+    #[arg(long, global = true)]
+    timestamp: Option<u64>,
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Json)]
+    output: OutputFormat,
+    #[command(subcommand)]
+    command: TopLevel,
+}
+
+#[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)]
+enum TopLevel {
+    /// Manage named sandbox addresses.
+    #[command(subcommand)]
+    Identity(IdentityCommand),
+    #[command(flatten)]
+    Contract(Command),
+}
+
+#[derive(Subcommand, Debug)]
+enum IdentityCommand {
+    /// Create a new address and store it under a name.
+    Generate { name: String },
+    /// List stored identities.
+    List,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let output = cli.output;
+    match execute(cli) {
+        Ok(value) => {
+            render::print(&value, output);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Run one command over RPC, or in the sandbox when no RPC URL is given.
+fn execute(cli: Cli) -> Result<Value, CliError> {
+    if cli.network.rpc_url.is_none() {
+        return execute_in_sandbox(cli.command, &cli.state, cli.timestamp);
+    }
+    match cli.command {
+        TopLevel::Identity(_) => Err(CliError::Input(
+            "identities are sandbox-only; pass addresses as strkeys".into(),
+        )),
+        TopLevel::Contract(command) => commands::run(command, &Rpc::connect(&cli.network)?),
+    }
+}
+
+/// Run one command and persist the sandbox if it succeeded.
+#[cfg(any(test, feature = "sandbox"))]
+fn execute_in_sandbox(
+    command: TopLevel,
+    state: &std::path::Path,
+    timestamp: Option<u64>,
+) -> Result<Value, CliError> {
+    use serde_json::json;
+
+    use crate::render::ToJson;
+    use crate::sandbox::Sandbox;
+
+    let mut sandbox = Sandbox::open(state, timestamp)?;
+    let value = match command {
+        TopLevel::Identity(IdentityCommand::Generate { name }) => {
+            let address = sandbox.generate_identity(&name)?;
+            json!({ "name": name, "address": address.to_json() })
+        }
+        TopLevel::Identity(IdentityCommand::List) => {
+            let identities: Vec<Value> = sandbox
+                .identities()
+                .iter()
+                .map(|(name, address)| json!({ "name": name, "address": address }))
+                .collect();
+            Value::Array(identities)
+        }
+        TopLevel::Contract(command) => commands::run(command, &sandbox)?,
+    };
+    sandbox.save()?;
+    Ok(value)
+}
+
+#[cfg(not(any(test, feature = "sandbox")))]
+fn execute_in_sandbox(
+    _command: TopLevel,
+    _state: &std::path::Path,
+    _timestamp: Option<u64>,
+) -> Result<Value, CliError> {
+    Err(CliError::Input(
+        "this build has no sandbox; pass --rpc-url and --contract, or rebuild with \
+         `--features sandbox`"
+            .into(),
+    ))
+}
@@ -0,0 +1,653 @@
+use chainlogistics::{
+    ActorGrant, ActorPage, ActorRole, AdminAction, AdminCouncil, AdminHandover, AdminProposal,
+    Attestation, CertIssuer, Device, EventChainReport, EventMerkleRoot, Lifecycle, Lineage,
+    LineageEntry, LineageOp, Location, PauseRecord, PauseScope, PendingTransfer, Product,
+    ProductMetadata, ProductPage, ProductStats, ProductVersion, ProposalStatus, Recall,
+    RecallScope, RecallSeverity, RecallStatus, TelemetryReading, TelemetryThresholds,
+    TrackingEvent, TrackingEventPage,
+};
+use clap::ValueEnum;
+use serde_json::{json, Map as JsonMap, Value};
+use soroban_sdk::{Address, Bytes, BytesN, Map, String, Symbol, Vec};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    Table,
+}
+
+/// Print a command result in the requested format.
+pub fn print(value: &Value, format: OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(value).unwrap_or_default()
+            )
+        }
+        OutputFormat::Table => print!("{}", table(value)),
+    }
+}
+
+// ─── Tables ──────────────────────────────────────────────────────────────────
+
+/// Render JSON as text. Lists of records become a table, a record becomes
+/// key/value rows, and a record holding one list of records (a page) prints
+/// its scalar fields followed by that list as a table.
+pub fn table(value: &Value) -> std::string::String {
+    match value {
+        Value::Array(items) if items.iter().all(Value::is_object) && !items.is_empty() => {
+            grid(items)
+        }
+        Value::Object(fields) => {
+            let rows: std::vec::Vec<(&str, &Value)> =
+                fields.iter().map(|(k, v)| (k.as_str(), v)).collect();
+            let list = rows.iter().find(|(_, v)| {
+                matches!(v, Value::Array(items) if !items.is_empty() && items.iter().all(Value::is_object))
+            });
+            match list {
+                Some((list_key, Value::Array(items))) => {
+                    let mut out = key_values(rows.iter().filter(|(k, _)| k != list_key).copied());
+                    out.push('\n');
+                    out.push_str(&grid(items));
+                    out
+                }
+                _ => key_values(rows.into_iter()),
+            }
+        }
+        other => format!("{}\n", cell(other)),
+    }
+}
+
+fn key_values<'a>(rows: impl Iterator<Item = (&'a str, &'a Value)>) -> std::string::String {
+    let rows: std::vec::Vec<_> = rows.collect();
+    let width = rows.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+    rows.iter()
+        .map(|(k, v)| format!("{k:<width$}  {}\n", cell(v)))
+        .collect()
+}
+
+fn grid(items: &[Value]) -> std::string::String {
+    let mut columns: std::vec::Vec<&str> = std::vec::Vec::new();
+    for item in items {
+        for key in item.as_object().into_iter().flat_map(|o| o.keys()) {
+            if !columns.contains(&key.as_str()) {
+                columns.push(key);
+            }
+        }
+    }
+    let rows: std::vec::Vec<std::vec::Vec<std::string::String>> = items
+        .iter()
+        .map(|item| columns.iter().map(|c| cell(&item[*c])).collect())
+        .collect();
+    let widths: std::vec::Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            rows.iter()
+                .map(|r| r[i].len())
+                .max()
+                .unwrap_or(0)
+                .max(c.len())
+        })
+        .collect();
+
+    let line = |cells: std::vec::Vec<&str>| -> std::string::String {
+        let padded: std::vec::Vec<std::string::String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(c, w)| format!("{c:<w$}"))
+            .collect();
+        format!("{}\n", padded.join("  ").trim_end())
+    };
+    let mut out = line(columns.clone());
+    for row in &rows {
+        out.push_str(&line(row.iter().map(std::string::String::as_str).collect()));
+    }
+    out
+}
+
+fn cell(value: &Value) -> std::string::String {
+    match value {
+        Value::Null => "-".into(),
+        Value::String(s) if s.is_empty() => "-".into(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) if items.iter().all(|v| !v.is_object() && !v.is_array()) => items
+            .iter()
+            .map(cell)
+            .collect::<std::vec::Vec<_>>()
+            .join(","),
+        other => other.to_string(),
+    }
+}
+
+// ─── Contract values ─────────────────────────────────────────────────────────
+
+/// Host JSON rendering of contract values.
+pub trait ToJson {
+    fn to_json(&self) -> Value;
+}
+
+impl ToJson for () {
+    fn to_json(&self) -> Value {
+        json!({ "ok": true })
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Value {
+        match self {
+            Some(value) => value.to_json(),
+            None => Value::Null,
+        }
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> Value {
+        json!(self)
+    }
+}
+
+impl ToJson for u32 {
+    fn to_json(&self) -> Value {
+        json!(self)
+    }
+}
+
+impl ToJson for u64 {
+    fn to_json(&self) -> Value {
+        json!(self)
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Value {
+        json!(self.to_string())
+    }
+}
+
+impl ToJson for Symbol {
+    fn to_json(&self) -> Value {
+        json!(self.to_string())
+    }
+}
+
+impl ToJson for Address {
+    fn to_json(&self) -> Value {
+        json!(self.to_string().to_string())
+    }
+}
+
+impl<const N: usize> ToJson for BytesN<N> {
+    fn to_json(&self) -> Value {
+        json!(self
+            .to_array()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<std::string::String>())
+    }
+}
+
+impl ToJson for Bytes {
+    fn to_json(&self) -> Value {
+        json!(self
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<std::string::String>())
+    }
+}
+
+impl<
+        T: ToJson
+            + Clone
+            + soroban_sdk::TryFromVal<soroban_sdk::Env, soroban_sdk::Val>
+            + soroban_sdk::IntoVal<soroban_sdk::Env, soroban_sdk::Val>,
+    > ToJson for Vec<T>
+{
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(|item| item.to_json()).collect())
+    }
+}
+
+impl ToJson for Map<Symbol, String> {
+    fn to_json(&self) -> Value {
+        let fields: JsonMap<_, _> = self
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_json()))
+            .collect();
+        Value::Object(fields)
+    }
+}
+
+impl ToJson for ActorRole {
+    fn to_json(&self) -> Value {
+        json!(format!("{self:?}"))
+    }
+}
+
+impl ToJson for LineageOp {
+    fn to_json(&self) -> Value {
+        json!(format!("{self:?}"))
+    }
+}
+
+impl ToJson for RecallSeverity {
+    fn to_json(&self) -> Value {
+        json!(format!("{self:?}"))
+    }
+}
+
+impl ToJson for RecallScope {
+    fn to_json(&self) -> Value {
+        match self {
+            RecallScope::Products(ids) => json!({ "products": ids.to_json() }),
+            RecallScope::Category(category) => json!({ "category": category.to_json() }),
+            RecallScope::Origin(origin) => json!({ "origin": origin.to_json() }),
+        }
+    }
+}
+
+impl ToJson for Location {
+    fn to_json(&self) -> Value {
+        let coordinates = self.coordinates.first().map(|c| {
+            json!({
+                "latitude": c.latitude as f64 / 1_000_000.0,
+                "longitude": c.longitude as f64 / 1_000_000.0,
+            })
+        });
+        json!({
+            "name": self.name.to_json(),
+            "facility_id": self.facility_id.to_json(),
+            "country": self.country.to_json(),
+            "geohash": self.geohash.to_json(),
+            "coordinates": coordinates,
+        })
+    }
+}
+
+impl ToJson for Product {
+    fn to_json(&self) -> Value {
+        let deactivation = self.deactivation_info.first().map(|d| {
+            json!({
+                "reason": d.reason.to_json(),
+                "deactivated_at": d.deactivated_at,
+                "deactivated_by": d.deactivated_by.to_json(),
+            })
+        });
+        json!({
+            "id": self.id.to_json(),
+            "name": self.name.to_json(),
+            "description": self.description.to_json(),
+            "origin": self.origin.location.to_json(),
+            "owner": self.owner.to_json(),
+            "created_at": self.created_at,
+            "active": self.active,
+            "category": self.category.to_json(),
+            "tags": self.tags.to_json(),
+            "certifications": self.certifications.to_json(),
+            "media_hashes": self.media_hashes.to_json(),
+            "custom": self.custom.to_json(),
+            "deactivation": deactivation,
+        })
+    }
+}
+
+impl ToJson for ProductStats {
+    fn to_json(&self) -> Value {
+        json!({
+            "total_products": self.total_products,
+            "active_products": self.active_products,
+        })
+    }
+}
+
+impl ToJson for ProductMetadata {
+    fn to_json(&self) -> Value {
+        json!({
+            "name": self.name.to_json(),
+            "description": self.description.to_json(),
+            "tags": self.tags.to_json(),
+            "media_hashes": self.media_hashes.to_json(),
+            "custom": self.custom.to_json(),
+        })
+    }
+}
+
+impl ToJson for ProductVersion {
+    fn to_json(&self) -> Value {
+        json!({
+            "version": self.version,
+            "editor": self.editor.to_json(),
+            "updated_at": self.updated_at,
+            "changed": self.changed.to_json(),
+            "previous_hash": self.previous_hash.to_json(),
+            "metadata": self.metadata.to_json(),
+        })
+    }
+}
+
+impl ToJson for ProductPage {
+    fn to_json(&self) -> Value {
+        json!({
+            "total_count": self.total_count,
+            "has_more": self.has_more,
+            "products": self.products.to_json(),
+        })
+    }
+}
+
+impl ToJson for TrackingEvent {
+    fn to_json(&self) -> Value {
+        json!({
+            "event_id": self.event_id,
+            "product_id": self.product_id.to_json(),
+            "actor": self.actor.to_json(),
+            "timestamp": self.timestamp,
+            "event_type": self.event_type.to_json(),
+            "location": self.location.name.to_json(),
+            "facility_id": self.location.facility_id.to_json(),
+            "data_hash": self.data_hash.to_json(),
+            "note": self.note.to_json(),
+            "metadata": self.metadata.to_json(),
+            "prev_hash": self.prev_hash.to_json(),
+        })
+    }
+}
+
+impl ToJson for TrackingEventPage {
+    fn to_json(&self) -> Value {
+        json!({
+            "total_count": self.total_count,
+            "total_is_estimate": self.total_is_estimate,
+            "has_more": self.has_more,
+            "next_cursor": self.next_cursor.to_json(),
+            "events": self.events.to_json(),
+        })
+    }
+}
+
+impl ToJson for EventChainReport {
+    fn to_json(&self) -> Value {
+        json!({
+            "valid": self.valid,
+            "events_checked": self.events_checked,
+            "broken_at": self.broken_at,
+        })
+    }
+}
+
+impl ToJson for EventMerkleRoot {
+    fn to_json(&self) -> Value {
+        json!({
+            "root": self.root.to_json(),
+            "size": self.size,
+        })
+    }
+}
+
+impl ToJson for ActorGrant {
+    fn to_json(&self) -> Value {
+        json!({
+            "actor": self.actor.to_json(),
+            "role": self.role.to_json(),
+            "valid_from": self.valid_from,
+            "valid_until": self.valid_until,
+        })
+    }
+}
+
+impl ToJson for ActorPage {
+    fn to_json(&self) -> Value {
+        json!({
+            "total_count": self.total_count,
+            "has_more": self.has_more,
+            "actors": self.actors.to_json(),
+        })
+    }
+}
+
+impl ToJson for PendingTransfer {
+    fn to_json(&self) -> Value {
+        json!({
+            "product_id": self.product_id.to_json(),
+            "from": self.from.to_json(),
+            "to": self.to.to_json(),
+            "created_at": self.created_at,
+            "expires_at": self.expires_at,
+        })
+    }
+}
+
+impl ToJson for LineageEntry {
+    fn to_json(&self) -> Value {
+        json!({
+            "product_id": self.product_id.to_json(),
+            "depth": self.depth,
+            "op": self.op.to_json(),
+        })
+    }
+}
+
+impl ToJson for Lineage {
+    fn to_json(&self) -> Value {
+        json!({
+            "product_id": self.product_id.to_json(),
+            "ancestors": self.ancestors.to_json(),
+            "descendants": self.descendants.to_json(),
+        })
+    }
+}
+
+impl ToJson for Recall {
+    fn to_json(&self) -> Value {
+        json!({
+            "recall_id": self.recall_id,
+            "initiator": self.initiator.to_json(),
+            "scope": self.scope.to_json(),
+            "reason": self.reason.to_json(),
+            "severity": self.severity.to_json(),
+            "initiated_at": self.initiated_at,
+            "affected": self.affected.to_json(),
+        })
+    }
+}
+
+impl ToJson for PauseScope {
+    fn to_json(&self) -> Value {
+        match self {
+            PauseScope::Product(id) => json!({ "product": id.to_json() }),
+            PauseScope::Category(category) => json!({ "category": category.to_json() }),
+            other => json!(format!("{other:?}")),
+        }
+    }
+}
+
+impl ToJson for PauseRecord {
+    fn to_json(&self) -> Value {
+        json!({
+            "scope": self.scope.to_json(),
+            "reason": self.reason.to_json(),
+            "paused_by": self.paused_by.to_json(),
+            "paused_at": self.paused_at,
+            "expires_at": self.expires_at,
+        })
+    }
+}
+
+impl ToJson for AdminCouncil {
+    fn to_json(&self) -> Value {
+        json!({
+            "signers": self.signers.to_json(),
+            "threshold": self.threshold,
+        })
+    }
+}
+
+impl ToJson for AdminAction {
+    fn to_json(&self) -> Value {
+        match self {
+            AdminAction::SetCouncil(signers, threshold) => json!({
+                "set_council": { "signers": signers.to_json(), "threshold": threshold },
+            }),
+            AdminAction::Upgrade(wasm_hash) => json!({ "upgrade": wasm_hash.to_json() }),
+            AdminAction::Pause(scope, reason, duration) => json!({
+                "pause": {
+                    "scope": scope.to_json(),
+                    "reason": reason.to_json(),
+                    "duration": duration,
+                },
+            }),
+            AdminAction::Unpause(scope) => json!({ "unpause": scope.to_json() }),
+            AdminAction::SetGuardian(guardian) => json!({ "set_guardian": guardian.to_json() }),
+            AdminAction::RemoveGuardian => json!("remove_guardian"),
+            AdminAction::SetCertIssuer(issuer, name, cert_types) => json!({
+                "set_cert_issuer": {
+                    "issuer": issuer.to_json(),
+                    "name": name.to_json(),
+                    "cert_types": cert_types.to_json(),
+                },
+            }),
+            AdminAction::RemoveCertIssuer(issuer) => {
+                json!({ "remove_cert_issuer": issuer.to_json() })
+            }
+            AdminAction::SetCategoryLifecycle(category, lifecycle) => json!({
+                "set_category_lifecycle": {
+                    "category": category.to_json(),
+                    "lifecycle": lifecycle.to_json(),
+                },
+            }),
+            AdminAction::RemoveCategoryLifecycle(category) => {
+                json!({ "remove_category_lifecycle": category.to_json() })
+            }
+            AdminAction::InitiateRecall(scope, reason, severity) => json!({
+                "initiate_recall": {
+                    "scope": scope.to_json(),
+                    "reason": reason.to_json(),
+                    "severity": severity.to_json(),
+                },
+            }),
+        }
+    }
+}
+
+impl ToJson for ProposalStatus {
+    fn to_json(&self) -> Value {
+        json!(format!("{self:?}"))
+    }
+}
+
+impl ToJson for AdminProposal {
+    fn to_json(&self) -> Value {
+        json!({
+            "proposal_id": self.proposal_id,
+            "action": self.action.to_json(),
+            "proposer": self.proposer.to_json(),
+            "approvals": self.approvals.to_json(),
+            "created_at": self.created_at,
+            "ready_at": self.ready_at,
+            "expires_at": self.expires_at,
+            "status": self.status.to_json(),
+        })
+    }
+}
+
+impl ToJson for AdminHandover {
+    fn to_json(&self) -> Value {
+        json!({
+            "from": self.from.to_json(),
+            "to": self.to.to_json(),
+            "created_at": self.created_at,
+            "ready_at": self.ready_at,
+            "expires_at": self.expires_at,
+        })
+    }
+}
+
+impl ToJson for RecallStatus {
+    fn to_json(&self) -> Value {
+        json!({
+            "recalled": self.recalled,
+            "recall": self.recall.first().map(|r| r.to_json()),
+        })
+    }
+}
+
+impl ToJson for Lifecycle {
+    fn to_json(&self) -> Value {
+        let transitions: std::vec::Vec<Value> = self
+            .transitions
+            .iter()
+            .map(|t| json!({ "from": t.from.to_json(), "to": t.to.to_json() }))
+            .collect();
+        json!({
+            "initial": self.initial.to_json(),
+            "transitions": transitions,
+        })
+    }
+}
+
+impl ToJson for TelemetryReading {
+    fn to_json(&self) -> Value {
+        json!({
+            "recorded_at": self.recorded_at,
+            "temperature": self.temperature,
+            "humidity": self.humidity,
+            "shock": self.shock,
+            "latitude": self.latitude as f64 / 1_000_000.0,
+            "longitude": self.longitude as f64 / 1_000_000.0,
+        })
+    }
+}
+
+impl ToJson for TelemetryThresholds {
+    fn to_json(&self) -> Value {
+        json!({
+            "min_temperature": self.min_temperature,
+            "max_temperature": self.max_temperature,
+            "max_humidity": self.max_humidity,
+            "max_shock": self.max_shock,
+        })
+    }
+}
+
+impl ToJson for Device {
+    fn to_json(&self) -> Value {
+        json!({
+            "public_key": self.public_key.to_json(),
+            "label": self.label.to_json(),
+            "sponsor": self.sponsor.to_json(),
+            "scope": self.scope.to_json(),
+            "active": self.active,
+            "registered_at": self.registered_at,
+            "rotated_to": self.rotated_to.to_json(),
+        })
+    }
+}
+
+impl ToJson for CertIssuer {
+    fn to_json(&self) -> Value {
+        json!({
+            "issuer": self.issuer.to_json(),
+            "name": self.name.to_json(),
+            "cert_types": self.cert_types.to_json(),
+            "active": self.active,
+        })
+    }
+}
+
+impl ToJson for Attestation {
+    fn to_json(&self) -> Value {
+        json!({
+            "attestation_id": self.attestation_id,
+            "issuer": self.issuer.to_json(),
+            "product_id": self.product_id.to_json(),
+            "cert_type": self.cert_type.to_json(),
+            "doc_hash": self.doc_hash.to_json(),
+            "issued_at": self.issued_at,
+            "expires_at": self.expires_at,
+            "revoked": self.revoked,
+        })
+    }
+}
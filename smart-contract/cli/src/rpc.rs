@@ -0,0 +1,392 @@
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clap::Args;
+use ed25519_dalek::{Signer, SigningKey};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use soroban_sdk::xdr::{
+    AccountId, DecoratedSignature, FromXdr, Hash, HostFunction, InvokeContractArgs,
+    InvokeHostFunctionOp, LedgerEntryData, LedgerKey, LedgerKeyAccount, Limits, Memo, MuxedAccount,
+    Operation, OperationBody, Preconditions, PublicKey, ReadXdr, ScVal, SequenceNumber, Signature,
+    SignatureHint, SorobanAuthorizationEntry, SorobanCredentials, SorobanTransactionData, ToXdr,
+    Transaction, TransactionEnvelope, TransactionExt, TransactionMeta, TransactionSignaturePayload,
+    TransactionSignaturePayloadTaggedTransaction, TransactionV1Envelope, Uint256, WriteXdr,
+};
+use soroban_sdk::{Address, Bytes, BytesN, Env, String, Val, Vec};
+
+use crate::backend::Backend;
+use crate::error::{contract_error, CliError};
+use crate::input;
+
+/// Environment variable holding the source account's `S…` secret seed.
+pub const SOURCE_KEY_VAR: &str = "CHAINLOGISTICS_SOURCE_KEY";
+
+/// Inclusion fee offered on top of the simulated resource fee, in stroops.
+const BASE_FEE: u32 = 100;
+
+/// How long to wait for a submitted transaction to be applied.
+const POLL_ATTEMPTS: u32 = 30;
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Flags selecting a deployed contract behind a Soroban RPC server.
+#[derive(Args, Debug, Default)]
+pub struct NetworkArgs {
+    /// Soroban RPC endpoint. When given, commands run against the deployed
+    /// contract instead of the local sandbox.
+    #[arg(long, global = true)]
+    pub rpc_url: Option<std::string::String>,
+    /// Contract ID (`C…`) to invoke over RPC.
+    #[arg(long, global = true)]
+    pub contract: Option<std::string::String>,
+    /// Defaults to the passphrase the RPC server reports.
+    #[arg(long, global = true)]
+    pub network_passphrase: Option<std::string::String>,
+    /// File holding the `S…` secret seed of the account that signs and pays
+    /// for transactions. Falls back to `$CHAINLOGISTICS_SOURCE_KEY`. Only
+    /// needed for commands that write.
+    #[arg(long, global = true)]
+    pub source_key_file: Option<PathBuf>,
+}
+
+/// A deployed contract reached through Soroban RPC.
+///
+/// Calls are simulated first. Read-only calls return the simulated result;
+/// calls that write are signed by the source account, submitted and awaited.
+/// The source account is the only signer, so it must be the address each
+/// call authorizes as.
+pub struct Rpc {
+    env: Env,
+    url: std::string::String,
+    contract_id: Address,
+    network_id: [u8; 32],
+    source: Option<SigningKey>,
+}
+
+impl Rpc {
+    pub fn connect(args: &NetworkArgs) -> Result<Self, CliError> {
+        let env = Env::default();
+        let url = args
+            .rpc_url
+            .clone()
+            .ok_or_else(|| CliError::Input("--rpc-url is required".into()))?;
+        let contract = args
+            .contract
+            .as_deref()
+            .filter(|id| id.starts_with('C') && input::is_strkey(id))
+            .ok_or_else(|| CliError::Input("--contract must be a `C…` contract ID".into()))?;
+        let contract_id = Address::from_string(&String::from_str(&env, contract));
+        let source = input::secret_seed(args.source_key_file.as_deref(), SOURCE_KEY_VAR)?
+            .map(|seed| SigningKey::from_bytes(&seed));
+
+        let mut rpc = Self {
+            env,
+            url,
+            contract_id,
+            network_id: [0; 32],
+            source,
+        };
+        let passphrase = match &args.network_passphrase {
+            Some(passphrase) => passphrase.clone(),
+            None => rpc.request("getNetwork", json!({}))?["passphrase"]
+                .as_str()
+                .ok_or_else(|| CliError::Rpc("getNetwork returned no passphrase".into()))?
+                .to_string(),
+        };
+        rpc.network_id = Sha256::digest(passphrase.as_bytes()).into();
+        Ok(rpc)
+    }
+
+    /// Send one JSON-RPC request and return its `result`.
+    fn request(&self, method: &str, params: Value) -> Result<Value, CliError> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response = ureq::post(&self.url)
+            .set("Content-Type", "application/json")
+            .send_string(&body.to_string())
+            .map_err(|e| CliError::Rpc(format!("{method}: {e}")))?
+            .into_string()
+            .map_err(|e| CliError::Rpc(format!("{method}: {e}")))?;
+        let mut response: Value =
+            serde_json::from_str(&response).map_err(|e| CliError::Rpc(format!("{method}: {e}")))?;
+        if let Some(error) = response.get("error") {
+            return Err(CliError::Rpc(format!("{method}: {error}")));
+        }
+        Ok(response["result"].take())
+    }
+
+    /// Account that builds transactions: the source key's, or the all-zero
+    /// account when only simulating reads.
+    fn source_account(&self) -> Uint256 {
+        Uint256(match &self.source {
+            Some(key) => key.verifying_key().to_bytes(),
+            None => [0; 32],
+        })
+    }
+
+    fn sequence_number(&self, account: &Uint256) -> Result<i64, CliError> {
+        let key = LedgerKey::Account(LedgerKeyAccount {
+            account_id: AccountId(PublicKey::PublicKeyTypeEd25519(account.clone())),
+        });
+        let result = self.request("getLedgerEntries", json!({ "keys": [to_base64(&key)?] }))?;
+        let entry = result["entries"][0]["xdr"]
+            .as_str()
+            .ok_or_else(|| CliError::Rpc("source account not found".into()))?;
+        match from_base64::<LedgerEntryData>(entry)? {
+            LedgerEntryData::Account(account) => Ok(account.seq_num.0),
+            _ => Err(CliError::Rpc("source key is not an account".into())),
+        }
+    }
+
+    /// Simulate `tx`, returning the call's result, the auth entries it needs
+    /// and the resources it uses.
+    fn simulate(&self, tx: &Transaction) -> Result<Simulation, CliError> {
+        let envelope = TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx: tx.clone(),
+            signatures: Default::default(),
+        });
+        let result = self.request(
+            "simulateTransaction",
+            json!({ "transaction": to_base64(&envelope)? }),
+        )?;
+        if let Some(error) = result.get("error").and_then(Value::as_str) {
+            return Err(match contract_error(error) {
+                Some(err) => CliError::Contract(err),
+                None => CliError::Host(error.to_string()),
+            });
+        }
+
+        let call = &result["results"][0];
+        let mut auth = std::vec::Vec::new();
+        for entry in call["auth"].as_array().into_iter().flatten() {
+            auth.push(from_base64::<SorobanAuthorizationEntry>(
+                entry.as_str().unwrap_or_default(),
+            )?);
+        }
+        Ok(Simulation {
+            result: from_base64(call["xdr"].as_str().unwrap_or_default())?,
+            auth,
+            data: from_base64(result["transactionData"].as_str().unwrap_or_default())?,
+            min_resource_fee: result["minResourceFee"]
+                .as_str()
+                .and_then(|fee| fee.parse().ok())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Sign `tx` as the source account, submit it and wait for its result.
+    fn submit(&self, key: &SigningKey, tx: Transaction) -> Result<ScVal, CliError> {
+        let payload = TransactionSignaturePayload {
+            network_id: Hash(self.network_id),
+            tagged_transaction: TransactionSignaturePayloadTaggedTransaction::Tx(tx.clone()),
+        };
+        let hash = Sha256::digest(to_xdr(&payload)?);
+        let public_key = key.verifying_key().to_bytes();
+        let signature = DecoratedSignature {
+            hint: SignatureHint(public_key[28..].try_into().unwrap_or_default()),
+            signature: Signature(
+                key.sign(&hash)
+                    .to_bytes()
+                    .to_vec()
+                    .try_into()
+                    .map_err(|e| CliError::Host(format!("{e:?}")))?,
+            ),
+        };
+        let envelope = TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx,
+            signatures: vec![signature]
+                .try_into()
+                .map_err(|e| CliError::Host(format!("{e:?}")))?,
+        });
+
+        let sent = self.request(
+            "sendTransaction",
+            json!({ "transaction": to_base64(&envelope)? }),
+        )?;
+        match sent["status"].as_str() {
+            Some("PENDING") | Some("DUPLICATE") => {}
+            status => {
+                return Err(CliError::Rpc(format!(
+                    "transaction rejected ({}): {}",
+                    status.unwrap_or("unknown"),
+                    sent["errorResultXdr"].as_str().unwrap_or_default()
+                )))
+            }
+        }
+        let hash = sent["hash"].as_str().unwrap_or_default().to_string();
+
+        for _ in 0..POLL_ATTEMPTS {
+            let tx = self.request("getTransaction", json!({ "hash": hash }))?;
+            match tx["status"].as_str() {
+                Some("SUCCESS") => {
+                    let meta = from_base64::<TransactionMeta>(
+                        tx["resultMetaXdr"].as_str().unwrap_or_default(),
+                    )?;
+                    return match meta {
+                        TransactionMeta::V3(v3) => v3
+                            .soroban_meta
+                            .map(|meta| meta.return_value)
+                            .ok_or_else(|| CliError::Rpc(format!("{hash}: no return value"))),
+                        _ => Err(CliError::Rpc(format!("{hash}: unexpected result meta"))),
+                    };
+                }
+                Some("FAILED") => {
+                    return Err(CliError::Rpc(format!(
+                        "transaction {hash} failed: {}",
+                        tx["resultXdr"].as_str().unwrap_or_default()
+                    )))
+                }
+                _ => thread::sleep(POLL_INTERVAL),
+            }
+        }
+        Err(CliError::Rpc(format!(
+            "transaction {hash} not applied in time"
+        )))
+    }
+
+    /// Convert between the host's values and their XDR form.
+    fn encode(&self, value: Val) -> Result<ScVal, CliError> {
+        let bytes: std::vec::Vec<u8> = value.to_xdr(&self.env).iter().collect();
+        <ScVal as ReadXdr>::from_xdr(bytes, Limits::none())
+            .map_err(|e| CliError::Host(e.to_string()))
+    }
+
+    fn decode(&self, value: &ScVal) -> Result<Val, CliError> {
+        let bytes = Bytes::from_slice(&self.env, &to_xdr(value)?);
+        Val::from_xdr(&self.env, &bytes).map_err(|e| CliError::Host(format!("{e:?}")))
+    }
+}
+
+/// What simulating a call reported.
+struct Simulation {
+    result: ScVal,
+    auth: std::vec::Vec<SorobanAuthorizationEntry>,
+    data: SorobanTransactionData,
+    min_resource_fee: u32,
+}
+
+impl Backend for Rpc {
+    fn env(&self) -> &Env {
+        &self.env
+    }
+
+    fn contract_id(&self) -> &Address {
+        &self.contract_id
+    }
+
+    fn network_id(&self) -> BytesN<32> {
+        BytesN::from_array(&self.env, &self.network_id)
+    }
+
+    fn timestamp(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default()
+    }
+
+    /// Only strkeys: named identities exist in the sandbox alone.
+    fn address(&self, strkey: &str) -> Result<Address, CliError> {
+        if !input::is_strkey(strkey) {
+            return Err(CliError::Input(format!(
+                "`{strkey}` is not a `G…` or `C…` strkey; identity names only work in the sandbox"
+            )));
+        }
+        Ok(Address::from_string(&String::from_str(&self.env, strkey)))
+    }
+
+    fn invoke(&self, function: &str, args: Vec<Val>) -> Result<Val, CliError> {
+        let ScVal::Address(contract_address) = self.encode(self.contract_id.to_val())? else {
+            return Err(CliError::Host("contract ID is not an address".into()));
+        };
+        let mut sc_args = std::vec::Vec::new();
+        for arg in args.iter() {
+            sc_args.push(self.encode(arg)?);
+        }
+        let invocation = InvokeContractArgs {
+            contract_address,
+            function_name: function
+                .try_into()
+                .map_err(|_| CliError::Input(format!("`{function}` is not a function name")))?,
+            args: sc_args
+                .try_into()
+                .map_err(|e| CliError::Host(format!("{e:?}")))?,
+        };
+
+        let account = self.source_account();
+        let sequence = match &self.source {
+            Some(_) => self.sequence_number(&account)?,
+            None => 0,
+        };
+        let mut tx = Transaction {
+            source_account: MuxedAccount::Ed25519(account),
+            fee: BASE_FEE,
+            seq_num: SequenceNumber(sequence + 1),
+            cond: Preconditions::None,
+            memo: Memo::None,
+            operations: vec![invoke_operation(invocation.clone(), vec![])?]
+                .try_into()
+                .map_err(|e| CliError::Host(format!("{e:?}")))?,
+            ext: TransactionExt::V0,
+        };
+
+        let simulation = self.simulate(&tx)?;
+        let writes = !simulation.data.resources.footprint.read_write.is_empty();
+        if !writes && simulation.auth.is_empty() {
+            return self.decode(&simulation.result);
+        }
+
+        let key = self.source.as_ref().ok_or_else(|| {
+            CliError::Input(format!(
+                "`{function}` writes to the ledger; pass --source-key-file or set ${SOURCE_KEY_VAR}"
+            ))
+        })?;
+        for entry in &simulation.auth {
+            if let SorobanCredentials::Address(credentials) = &entry.credentials {
+                return Err(CliError::Input(format!(
+                    "`{function}` needs a signature from {}, which is not the source account",
+                    credentials.address
+                )));
+            }
+        }
+
+        tx.fee = BASE_FEE.saturating_add(simulation.min_resource_fee);
+        tx.operations = vec![invoke_operation(invocation, simulation.auth)?]
+            .try_into()
+            .map_err(|e| CliError::Host(format!("{e:?}")))?;
+        tx.ext = TransactionExt::V1(simulation.data);
+        let result = self.submit(key, tx)?;
+        self.decode(&result)
+    }
+}
+
+fn invoke_operation(
+    invocation: InvokeContractArgs,
+    auth: std::vec::Vec<SorobanAuthorizationEntry>,
+) -> Result<Operation, CliError> {
+    Ok(Operation {
+        source_account: None,
+        body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+            host_function: HostFunction::InvokeContract(invocation),
+            auth: auth
+                .try_into()
+                .map_err(|e| CliError::Host(format!("{e:?}")))?,
+        }),
+    })
+}
+
+fn to_xdr(value: &impl WriteXdr) -> Result<std::vec::Vec<u8>, CliError> {
+    value
+        .to_xdr(Limits::none())
+        .map_err(|e| CliError::Host(e.to_string()))
+}
+
+fn to_base64(value: &impl WriteXdr) -> Result<std::string::String, CliError> {
+    value
+        .to_xdr_base64(Limits::none())
+        .map_err(|e| CliError::Host(e.to_string()))
+}
+
+fn from_base64<T: ReadXdr>(b64: &str) -> Result<T, CliError> {
+    T::from_xdr_base64(b64, Limits::none()).map_err(|e| CliError::Rpc(format!("bad XDR: {e}")))
+}
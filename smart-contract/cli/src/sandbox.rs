@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chainlogistics::ChainLogisticsContract;
+use serde::{Deserialize, Serialize};
+use soroban_sdk::testutils::{Address as _, EnvTestConfig, Ledger};
+use soroban_sdk::{Address, BytesN, Env, String, Symbol, Val, Vec};
+
+use crate::backend::Backend;
+use crate::error::{invoke, CliError};
+use crate::input;
+
+const LEDGER_FILE: &str = "ledger.json";
+const STATE_FILE: &str = "sandbox.json";
+
+/// Contract ID and named identities, kept next to the ledger snapshot.
+#[derive(Default, Serialize, Deserialize)]
+struct SandboxState {
+    contract_id: Option<std::string::String>,
+    identities: BTreeMap<std::string::String, std::string::String>,
+}
+
+/// A local, in-process ledger running the contract natively.
+///
+/// Ledger state lives in `<dir>/ledger.json`, in the same snapshot format as
+/// `contracts/test_snapshots/`, so it can be fed to `chainlogistics-epcis`.
+/// Authorization is mocked: commands act as whichever address they name.
+pub struct Sandbox {
+    dir: PathBuf,
+    env: Env,
+    contract_id: Address,
+    state: SandboxState,
+}
+
+impl Sandbox {
+    /// Load the sandbox in `dir`, creating a fresh ledger and contract
+    /// instance on first use. The ledger clock is set to `timestamp`, or the
+    /// wall clock when `None`.
+    pub fn open(dir: &Path, timestamp: Option<u64>) -> Result<Self, CliError> {
+        let ledger_path = dir.join(LEDGER_FILE);
+        let env = if ledger_path.exists() {
+            let snapshot = soroban_sdk::testutils::Snapshot::read_file(&ledger_path)
+                .map_err(|e| CliError::Io(format!("{}: {e}", ledger_path.display())))?;
+            Env::from_snapshot(snapshot)
+        } else {
+            Env::new_with_config(EnvTestConfig {
+                capture_snapshot_at_drop: false,
+            })
+        };
+        env.mock_all_auths();
+
+        let now = match timestamp {
+            Some(ts) => ts,
+            None => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_err(|e| CliError::Io(e.to_string()))?
+                .as_secs(),
+        };
+        env.ledger().with_mut(|ledger| {
+            ledger.timestamp = now.max(ledger.timestamp);
+            ledger.sequence_number += 1;
+        });
+        // Every Env starts from the same PRNG seed, so mocked auth would hand
+        // out the nonces of earlier runs again, which the ledger still holds.
+        // Seeding from the ledger sequence keeps each run's nonces fresh.
+        let mut seed = [0u8; 32];
+        seed[..4].copy_from_slice(&env.ledger().sequence().to_be_bytes());
+        env.host()
+            .set_base_prng_seed(seed)
+            .map_err(|e| CliError::Host(format!("{e:?}")))?;
+
+        let state_path = dir.join(STATE_FILE);
+        let mut state: SandboxState = if state_path.exists() {
+            serde_json::from_str(&fs::read_to_string(&state_path)?)
+                .map_err(|e| CliError::Io(format!("{}: {e}", state_path.display())))?
+        } else {
+            SandboxState::default()
+        };
+
+        let existing = state
+            .contract_id
+            .as_deref()
+            .map(|id| Address::from_string(&String::from_str(&env, id)));
+        let contract_id = env.register_contract(existing.as_ref(), ChainLogisticsContract);
+        state.contract_id = Some(contract_id.to_string().to_string());
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            env,
+            contract_id,
+            state,
+        })
+    }
+
+    /// Create a fresh address and remember it under `name`.
+    pub fn generate_identity(&mut self, name: &str) -> Result<Address, CliError> {
+        if self.state.identities.contains_key(name) {
+            return Err(CliError::Input(format!("identity `{name}` already exists")));
+        }
+        let address = Address::generate(&self.env);
+        self.state
+            .identities
+            .insert(name.into(), address.to_string().to_string());
+        Ok(address)
+    }
+
+    pub fn identities(&self) -> &BTreeMap<std::string::String, std::string::String> {
+        &self.state.identities
+    }
+
+    /// Persist the ledger and identities. Only called after a command
+    /// succeeds, so a failed invocation leaves the sandbox untouched.
+    pub fn save(&self) -> Result<(), CliError> {
+        fs::create_dir_all(&self.dir)?;
+        self.env
+            .to_snapshot()
+            .write_file(self.dir.join(LEDGER_FILE))
+            .map_err(|e| CliError::Io(e.to_string()))?;
+        let state =
+            serde_json::to_string_pretty(&self.state).map_err(|e| CliError::Io(e.to_string()))?;
+        fs::write(self.dir.join(STATE_FILE), state)?;
+        Ok(())
+    }
+}
+
+impl Backend for Sandbox {
+    fn env(&self) -> &Env {
+        &self.env
+    }
+
+    fn contract_id(&self) -> &Address {
+        &self.contract_id
+    }
+
+    fn network_id(&self) -> BytesN<32> {
+        self.env.ledger().network_id()
+    }
+
+    fn timestamp(&self) -> u64 {
+        self.env.ledger().timestamp()
+    }
+
+    fn address(&self, name_or_strkey: &str) -> Result<Address, CliError> {
+        let strkey = match self.state.identities.get(name_or_strkey) {
+            Some(strkey) => strkey.as_str(),
+            None if input::is_strkey(name_or_strkey) => name_or_strkey,
+            None => {
+                return Err(CliError::Input(format!(
+                    "unknown identity `{name_or_strkey}`; create it with `identity generate`"
+                )))
+            }
+        };
+        Ok(Address::from_string(&String::from_str(&self.env, strkey)))
+    }
+
+    fn invoke(&self, function: &str, args: Vec<Val>) -> Result<Val, CliError> {
+        let function = Symbol::new(&self.env, function);
+        invoke(self.env.try_invoke_contract::<Val, chainlogistics::Error>(
+            &self.contract_id,
+            &function,
+            args,
+        ))
+    }
+}
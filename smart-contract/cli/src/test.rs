@@ -0,0 +1,452 @@
+use std::path::{Path, PathBuf};
+
+use chainlogistics_epcis::{import_batches, ImportOptions};
+use clap::{CommandFactory, Parser};
+use serde_json::{json, Value};
+use soroban_sdk::testutils::EnvTestConfig;
+use soroban_sdk::{Env, String, Symbol};
+
+use crate::error::{self, CliError};
+use crate::input::{self, ProductConfigFile, ProductConfigs};
+use crate::render::table;
+use crate::{execute, Cli};
+
+// ─── Test helpers ─────────────────────────────────────────────────────────────
+
+/// A scratch sandbox directory, removed on drop.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let dir =
+            std::env::temp_dir().join(format!("chainlogistics-cli-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// An `Env` that does not write a test snapshot when dropped.
+fn env() -> Env {
+    Env::new_with_config(EnvTestConfig {
+        capture_snapshot_at_drop: false,
+    })
+}
+
+/// Parse and execute one command line against `state`.
+fn run(state: &Path, args: &[&str]) -> Result<Value, CliError> {
+    let mut argv = vec![
+        "chainlogistics",
+        "--state",
+        state.to_str().unwrap(),
+        "--timestamp",
+        "1700000000",
+    ];
+    argv.extend_from_slice(args);
+    let cli = Cli::try_parse_from(argv).expect("arguments should parse");
+    // Reloaded sandbox ledgers write a test snapshot when dropped on a named
+    // thread; an unnamed one keeps them out of the source tree.
+    std::thread::Builder::new()
+        .spawn(move || execute(cli))
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+const PRODUCT_TOML: &str = r#"
+id = "COFFEE-001"
+name = "Single-origin coffee"
+description = "Washed Arabica"
+category = "coffee"
+tags = ["organic", "fair_trade"]
+
+[origin]
+name = "Yirgacheffe"
+country = "ET"
+latitude = 6.1621
+longitude = 38.2061
+
+[custom]
+variety = "heirloom"
+"#;
+
+// ═══ ARGUMENTS ═══
+
+#[test]
+fn test_cli_definition_is_consistent() {
+    Cli::command().debug_assert();
+}
+
+#[test]
+fn test_product_config_from_toml() {
+    let env = env();
+    let file: ProductConfigFile = toml::from_str(PRODUCT_TOML).unwrap();
+    let config = file.to_contract(&env).unwrap();
+
+    assert_eq!(config.id, String::from_str(&env, "COFFEE-001"));
+    assert_eq!(config.tags.len(), 2);
+    assert_eq!(config.origin.country, String::from_str(&env, "ET"));
+    let coordinates = config.origin.coordinates.get(0).unwrap();
+    assert_eq!(coordinates.latitude, 6_162_100);
+    assert_eq!(coordinates.longitude, 38_206_100);
+    assert_eq!(
+        config.custom.get(Symbol::new(&env, "variety")),
+        Some(String::from_str(&env, "heirloom"))
+    );
+}
+
+#[test]
+fn test_product_configs_accept_one_or_many() {
+    let one: ProductConfigs = serde_json::from_str(r#"{"id": "A"}"#).unwrap();
+    assert_eq!(one.into_vec().len(), 1);
+
+    let many: ProductConfigs = serde_json::from_str(r#"[{"id": "A"}, {"id": "B"}]"#).unwrap();
+    let ids: Vec<_> = many.into_vec().into_iter().map(|c| c.id).collect();
+    assert_eq!(ids, ["A", "B"]);
+}
+
+#[test]
+fn test_product_config_rejects_unknown_fields_and_half_coordinates() {
+    assert!(serde_json::from_str::<ProductConfigFile>(r#"{"id": "A", "colour": "red"}"#).is_err());
+
+    let env = env();
+    let file: ProductConfigFile =
+        serde_json::from_str(r#"{"id": "A", "origin": {"latitude": 1.0}}"#).unwrap();
+    assert!(matches!(file.to_contract(&env), Err(CliError::Input(_))));
+}
+
+#[test]
+fn test_scalar_argument_parsing() {
+    let env = env();
+
+    assert_eq!(input::hex_bytes::<2>("0xbeef").unwrap(), [0xbe, 0xef]);
+    assert!(input::hex_bytes::<2>("beefed").is_err());
+    assert!(input::hex_bytes::<2>("zzzz").is_err());
+    assert_eq!(input::bytes(&env, "0x00ff").unwrap().len(), 2);
+    assert!(input::bytes(&env, "").unwrap().is_empty());
+    assert!(input::bytes(&env, "abc").is_err());
+
+    assert!(input::symbol(&env, "in_transit").is_ok());
+    assert!(input::symbol(&env, "in transit").is_err());
+    assert!(input::symbol(&env, &"x".repeat(33)).is_err());
+
+    let meta = input::metadata(&env, &["temp=4C".into(), "note=a=b".into()]).unwrap();
+    assert_eq!(
+        meta.get(Symbol::new(&env, "note")),
+        Some(String::from_str(&env, "a=b"))
+    );
+    assert!(input::metadata(&env, &["missing".into()]).is_err());
+}
+
+#[test]
+fn test_secrets_read_from_file_or_environment() {
+    let dir = TempDir::new("secrets");
+    let key_file = dir.path().join("device.key");
+    std::fs::write(&key_file, format!("{}\n", "ab".repeat(32))).unwrap();
+    let var = "CHAINLOGISTICS_TEST_SECRET";
+
+    assert_eq!(input::device_key(Some(&key_file), var).unwrap(), [0xab; 32]);
+    assert!(matches!(
+        input::device_key(None, var),
+        Err(CliError::Input(_))
+    ));
+    assert!(input::device_key(Some(&dir.path().join("missing")), var).is_err());
+
+    // Source accounts take an `S…` seed, never a raw hex key.
+    assert_eq!(input::secret_seed(None, var).unwrap(), None);
+    assert!(input::secret_seed(Some(&key_file), var).is_err());
+    let seed_file = dir.path().join("source.key");
+    let seed = stellar_strkey::ed25519::PrivateKey([7; 32]).to_string();
+    std::fs::write(&seed_file, seed.as_str()).unwrap();
+    assert_eq!(
+        input::secret_seed(Some(&seed_file), var).unwrap(),
+        Some([7; 32])
+    );
+}
+
+#[test]
+fn test_contract_error_from_diagnostic() {
+    assert_eq!(
+        error::contract_error("HostError: Error(Contract, #2)\n\nEvent log: ..."),
+        Some(chainlogistics::Error::ProductNotFound)
+    );
+    assert_eq!(
+        error::contract_error("HostError: Error(Auth, InvalidAction)"),
+        None
+    );
+    assert_eq!(error::contract_error("Error(Contract, #99999)"), None);
+}
+
+// ═══ RENDERING ═══
+
+#[test]
+fn test_table_renders_pages_and_lists() {
+    let page = json!({
+        "total_count": 2,
+        "has_more": false,
+        "events": [
+            { "event_id": 1, "event_type": "harvested", "note": "" },
+            { "event_id": 2, "event_type": "shipped", "note": "to port" },
+        ],
+    });
+    let text = table(&page);
+    let lines: Vec<&str> = text.lines().collect();
+    assert!(lines.contains(&"has_more     false"));
+    assert!(lines.contains(&"total_count  2"));
+    assert!(lines.contains(&"event_id  event_type  note"));
+    assert!(lines.contains(&"1         harvested   -"));
+    assert!(lines.contains(&"2         shipped     to port"));
+
+    assert_eq!(table(&json!(["a", "b"])), "a,b\n");
+    assert_eq!(table(&json!(true)), "true\n");
+}
+
+// ═══ SANDBOX ═══
+
+#[test]
+fn test_sandbox_round_trip() {
+    let dir = TempDir::new("round-trip");
+    let state = dir.path();
+    let config = state.join("coffee.toml");
+    std::fs::write(&config, PRODUCT_TOML).unwrap();
+
+    run(state, &["identity", "generate", "farm"]).unwrap();
+    let listed = run(state, &["identity", "list"]).unwrap();
+    assert_eq!(listed[0]["name"], "farm");
+
+    let product = run(
+        state,
+        &[
+            "register-product",
+            "--owner",
+            "farm",
+            "--config",
+            config.to_str().unwrap(),
+        ],
+    )
+    .unwrap();
+    assert_eq!(product["id"], "COFFEE-001");
+    assert_eq!(product["owner"], listed[0]["address"]);
+
+    // A fresh process reopens the same ledger and contract instance.
+    run(
+        state,
+        &[
+            "add-tracking-event",
+            "--actor",
+            "farm",
+            "--product",
+            "COFFEE-001",
+            "--event-type",
+            "harvested",
+            "--location",
+            "Yirgacheffe",
+            "--meta",
+            "lot=7",
+        ],
+    )
+    .unwrap();
+    let page = run(state, &["get-product-events", "COFFEE-001"]).unwrap();
+    assert_eq!(page["total_count"], 1);
+    assert_eq!(page["events"][0]["event_type"], "harvested");
+    assert_eq!(page["events"][0]["metadata"]["lot"], "7");
+    assert_eq!(page["next_cursor"], "");
+
+    // Page through with the returned cursor.
+    run(
+        state,
+        &[
+            "add-tracking-event",
+            "--actor",
+            "farm",
+            "--product",
+            "COFFEE-001",
+            "--event-type",
+            "shipped",
+            "--location",
+            "Djibouti",
+        ],
+    )
+    .unwrap();
+    let first = run(state, &["get-product-events", "COFFEE-001", "--limit", "1"]).unwrap();
+    assert_eq!(first["has_more"], true);
+    let cursor = first["next_cursor"].as_str().unwrap();
+    let next = run(state, &["get-next-events", cursor]).unwrap();
+    assert_eq!(next["events"][0]["event_type"], "shipped");
+    assert_eq!(next["has_more"], false);
+}
+
+#[test]
+fn test_sandbox_imports_epcis_batches() {
+    let dir = TempDir::new("import");
+    let state = dir.path();
+    let config = state.join("coffee.toml");
+    std::fs::write(&config, PRODUCT_TOML).unwrap();
+
+    run(state, &["identity", "generate", "farm"]).unwrap();
+    run(
+        state,
+        &[
+            "register-product",
+            "--owner",
+            "farm",
+            "--config",
+            config.to_str().unwrap(),
+        ],
+    )
+    .unwrap();
+
+    let document = json!({
+        "epcisBody": { "eventList": [
+            {
+                "type": "ObjectEvent",
+                "epcList": ["urn:chainlogistics:product:COFFEE-001"],
+                "bizStep": "commissioning",
+                "readPoint": { "id": "geo:6.1621,38.2061" }
+            },
+            {
+                "type": "ObjectEvent",
+                "epcList": ["urn:chainlogistics:product:COFFEE-001"],
+                "cl:eventType": "shipped",
+                "cl:metadata": { "vessel": "MSC Aurora" }
+            }
+        ]}
+    });
+    let batches = import_batches(&document, &ImportOptions::default()).unwrap();
+    let batches_file = state.join("batches.json");
+    std::fs::write(&batches_file, serde_json::to_string(&batches).unwrap()).unwrap();
+
+    let imported = run(
+        state,
+        &[
+            "import-events",
+            "--actor",
+            "farm",
+            batches_file.to_str().unwrap(),
+        ],
+    )
+    .unwrap();
+    assert_eq!(imported["event_ids"].as_array().unwrap().len(), 2);
+
+    let page = run(state, &["get-product-events", "COFFEE-001"]).unwrap();
+    assert_eq!(page["total_count"], 2);
+    assert_eq!(page["events"][1]["event_type"], "shipped");
+    assert_eq!(page["events"][1]["metadata"]["vessel"], "MSC Aurora");
+}
+
+#[test]
+fn test_sandbox_device_event_signed_from_key_file() {
+    let dir = TempDir::new("device");
+    let state = dir.path();
+    let config = state.join("coffee.toml");
+    std::fs::write(&config, PRODUCT_TOML).unwrap();
+    let key_file = state.join("sensor.key");
+    std::fs::write(&key_file, "11".repeat(32)).unwrap();
+    let key_file = key_file.to_str().unwrap();
+
+    run(state, &["identity", "generate", "farm"]).unwrap();
+    run(
+        state,
+        &[
+            "register-product",
+            "--owner",
+            "farm",
+            "--config",
+            config.to_str().unwrap(),
+        ],
+    )
+    .unwrap();
+
+    let public_key = run(state, &["device-public-key", "--secret-key-file", key_file]).unwrap();
+    let public_key = public_key.as_str().unwrap();
+    run(
+        state,
+        &[
+            "register-device",
+            "--sponsor",
+            "farm",
+            "--public-key",
+            public_key,
+            "--label",
+            "reefer-7",
+            "--scope",
+            "COFFEE-001",
+        ],
+    )
+    .unwrap();
+
+    let added = run(
+        state,
+        &[
+            "add-device-event",
+            "--secret-key-file",
+            key_file,
+            "--product",
+            "COFFEE-001",
+            "--event-type",
+            "harvested",
+        ],
+    )
+    .unwrap();
+    let event_id = added["event_id"].to_string();
+    let device = run(state, &["get-event-device", &event_id]).unwrap();
+    assert_eq!(device, public_key);
+
+    // A key the device was not registered with is turned away.
+    let other_key = state.join("other.key");
+    std::fs::write(&other_key, "22".repeat(32)).unwrap();
+    let err = run(
+        state,
+        &[
+            "add-device-event",
+            "--secret-key-file",
+            other_key.to_str().unwrap(),
+            "--product",
+            "COFFEE-001",
+            "--event-type",
+            "harvested",
+        ],
+    )
+    .unwrap_err();
+    assert!(matches!(err, CliError::Contract(_)));
+}
+
+#[test]
+fn test_failed_command_reports_contract_error() {
+    let dir = TempDir::new("errors");
+    let state = dir.path();
+
+    let err = run(state, &["get-product", "MISSING"]).unwrap_err();
+    assert!(matches!(
+        err,
+        CliError::Contract(chainlogistics::Error::ProductNotFound)
+    ));
+
+    let err = run(state, &["get-product", "MISSING", "--output", "table"]).unwrap_err();
+    assert!(err.to_string().contains("ProductNotFound"));
+
+    let err = run(
+        state,
+        &[
+            "add-tracking-event",
+            "--actor",
+            "nobody",
+            "--product",
+            "X",
+            "--event-type",
+            "x",
+        ],
+    )
+    .unwrap_err();
+    assert!(matches!(err, CliError::Input(_)));
+}
@@ -1,24 +1,28 @@
-use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, Map, String, Symbol, Vec};
+use soroban_sdk::{
+    contract, contractimpl,
+    xdr::{FromXdr, ToXdr},
+    Address, Bytes, BytesN, Env, IntoVal, Map, String, Symbol, Val, Vec,
+};
 
+use crate::error::Error;
 use crate::types::{
-    DeactInfo, Origin, Product, ProductConfig, ProductStats, TrackingEvent,
-    TrackingEventFilter, TrackingEventPage,
+    ActorGrant, ActorPage, ActorRole, AdminAction, AdminCouncil, AdminHandover, AdminProposal,
+    Attestation, CertIssuer, Coordinates, DeactInfo, Device, EventChainReport, EventCursor,
+    EventMerkleRoot, EventMerkleTree, EventQuery, Lifecycle, Lineage, LineageEntry, LineageOp,
+    LineageRecord, Location, Origin, PauseRecord, PauseScope, PendingTransfer, Product,
+    ProductConfig, ProductIndex, ProductMetadata, ProductPage, ProductStats, ProductVersion,
+    ProposalStatus, Recall, RecallScope, RecallSeverity, RecallStatus, TelemetryReading,
+    TelemetryThresholds, TrackingEvent, TrackingEventFilter, TrackingEventInput, TrackingEventPage,
 };
-use crate::error::Error;
 use crate::{storage, validation};
 
 // ─── Internal helpers ────────────────────────────────────────────────────────
 
-<<<<<<< nnennaokoye/chainlogistics
-fn require_not_paused(env: &Env) -> Result<(), Error> {
-    if storage::is_paused(env) {
-        return Err(Error::ContractPaused);
-    }
-    Ok(())
-}
-
 fn require_admin(env: &Env, caller: &Address) -> Result<(), Error> {
     let admin = storage::get_admin(env).ok_or(Error::NotInitialized)?;
+    if storage::get_admin_council(env).is_some() {
+        return Err(Error::CouncilActive);
+    }
     caller.require_auth();
     if &admin != caller {
         return Err(Error::Unauthorized);
@@ -27,16 +31,14 @@ fn require_admin(env: &Env, caller: &Address) -> Result<(), Error> {
 }
 
 /// Reads a product from persistent storage.
-/// 
+///
 /// Returns an error if the product doesn't exist.
-=======
->>>>>>> main
 fn read_product(env: &Env, product_id: &String) -> Result<Product, Error> {
     storage::get_product(env, product_id).ok_or(Error::ProductNotFound)
 }
 
 /// Writes a product to persistent storage.
-/// 
+///
 /// Products are stored using Soroban's persistent storage API,
 /// ensuring they persist across contract calls and ledger entries.
 fn write_product(env: &Env, product: &Product) {
@@ -52,740 +54,3196 @@ fn require_owner(product: &Product, caller: &Address) -> Result<(), Error> {
     Ok(())
 }
 
-/// Verify that an actor is allowed to add events:
-///  - product must be active
-///  - caller must be the owner or an explicitly authorized actor
-fn require_can_add_event(
-    env: &Env,
-    product_id: &String,
-    product: &Product,
-    caller: &Address,
-) -> Result<(), Error> {
-    caller.require_auth();
+/// Validate the lengths and coordinates of a location. Whether `name` may be
+/// empty is checked by callers, since origins require one and events do not.
+fn validate_location(location: &Location) -> Result<(), Error> {
+    const MAX_LOCATION_NAME_LEN: u32 = 128;
+    const MAX_FACILITY_ID_LEN: u32 = 64;
+    const MAX_LATITUDE: i32 = 90_000_000;
+    const MAX_LONGITUDE: i32 = 180_000_000;
+
+    if !validation::max_len(&location.name, MAX_LOCATION_NAME_LEN)
+        || !validation::max_len(&location.facility_id, MAX_FACILITY_ID_LEN)
+    {
+        return Err(Error::InvalidInput);
+    }
+    if validation::non_empty(&location.country) && !validation::is_country_code(&location.country) {
+        return Err(Error::InvalidInput);
+    }
+    if validation::non_empty(&location.geohash) && !validation::is_geohash(&location.geohash) {
+        return Err(Error::InvalidInput);
+    }
+    if location.coordinates.len() > 1 {
+        return Err(Error::InvalidInput);
+    }
+    if let Some(c) = location.coordinates.first() {
+        if c.latitude.abs() > MAX_LATITUDE || c.longitude.abs() > MAX_LONGITUDE {
+            return Err(Error::InvalidInput);
+        }
+    }
+    Ok(())
+}
 
-    // Deactivated products reject all new events
-    if !product.active {
-        return Err(Error::ProductDeactivated);
+/// Validate the editable product fields, with the same limits at
+/// registration and on every later update.
+fn validate_metadata(metadata: &ProductMetadata) -> Result<(), Error> {
+    const MAX_NAME_LEN: u32 = 128;
+    const MAX_DESC_LEN: u32 = 512;
+    const MAX_TAGS: u32 = 20;
+    const MAX_TAG_LEN: u32 = 64;
+    const MAX_MEDIA: u32 = 50;
+    const MAX_CUSTOM: u32 = 20;
+    const MAX_CUSTOM_VAL_LEN: u32 = 256;
+
+    if !validation::non_empty(&metadata.name) {
+        return Err(Error::InvalidProductName);
+    }
+    if !validation::max_len(&metadata.name, MAX_NAME_LEN) {
+        return Err(Error::InvalidProductName);
+    }
+    if !validation::max_len(&metadata.description, MAX_DESC_LEN) {
+        return Err(Error::InvalidInput);
+    }
+    if metadata.tags.len() > MAX_TAGS {
+        return Err(Error::TooManyTags);
     }
+    for i in 0..metadata.tags.len() {
+        if !validation::max_len(&metadata.tags.get_unchecked(i), MAX_TAG_LEN) {
+            return Err(Error::InvalidInput);
+        }
+    }
+    if metadata.media_hashes.len() > MAX_MEDIA {
+        return Err(Error::TooManyMediaHashes);
+    }
+    if metadata.custom.len() > MAX_CUSTOM {
+        return Err(Error::TooManyCustomFields);
+    }
+    let custom_keys = metadata.custom.keys();
+    for i in 0..custom_keys.len() {
+        let v = metadata.custom.get_unchecked(custom_keys.get_unchecked(i));
+        if !validation::max_len(&v, MAX_CUSTOM_VAL_LEN) {
+            return Err(Error::CustomFieldValueTooLong);
+        }
+    }
+    Ok(())
+}
 
-    // Owner always has permission
-    if &product.owner == caller {
-        return Ok(());
+/// The editable fields of a product as they currently stand.
+fn product_metadata(product: &Product) -> ProductMetadata {
+    ProductMetadata {
+        name: product.name.clone(),
+        description: product.description.clone(),
+        tags: product.tags.clone(),
+        media_hashes: product.media_hashes.clone(),
+        custom: product.custom.clone(),
     }
+}
 
-    // Check explicit authorization
-    if !storage::is_authorized(env, product_id, caller) {
-        return Err(Error::Unauthorized);
+/// Names of the metadata fields that differ between `old` and `new`.
+fn changed_fields(env: &Env, old: &ProductMetadata, new: &ProductMetadata) -> Vec<Symbol> {
+    let mut changed = Vec::new(env);
+    if old.name != new.name {
+        changed.push_back(Symbol::new(env, "name"));
     }
-    Ok(())
+    if old.description != new.description {
+        changed.push_back(Symbol::new(env, "description"));
+    }
+    if old.tags != new.tags {
+        changed.push_back(Symbol::new(env, "tags"));
+    }
+    if old.media_hashes != new.media_hashes {
+        changed.push_back(Symbol::new(env, "media_hashes"));
+    }
+    if old.custom != new.custom {
+        changed.push_back(Symbol::new(env, "custom"));
+    }
+    changed
 }
 
-fn require_can_add_event(env: &Env, product_id: &String, product: &Product, caller: &Address) -> Result<(), Error> {
-    require_can_add_event_internal(env, product_id, product, caller, true)
+/// Append the product's current metadata to its version history.
+/// `previous` is the metadata it replaces, or `None` at registration.
+fn record_version(
+    env: &Env,
+    product: &Product,
+    editor: &Address,
+    previous: Option<&ProductMetadata>,
+) -> ProductVersion {
+    let metadata = product_metadata(product);
+    let (changed, previous_hash) = match previous {
+        Some(old) => (
+            changed_fields(env, old, &metadata),
+            env.crypto().sha256(&old.clone().to_xdr(env)),
+        ),
+        None => (Vec::new(env), BytesN::from_array(env, &[0u8; 32])),
+    };
+
+    let version = ProductVersion {
+        version: storage::get_product_version_count(env, &product.id) + 1,
+        editor: editor.clone(),
+        updated_at: env.ledger().timestamp(),
+        metadata,
+        changed,
+        previous_hash,
+    };
+    storage::put_product_version(env, &product.id, &version);
+    version
 }
 
-fn page_from_vec(env: &Env, ids: &Vec<u64>, cursor: u32, limit: u32) -> EventIdPage {
-    if limit == 0 {
-        return EventIdPage {
-            ids: Vec::new(env),
-            next_cursor: cursor,
-        };
+/// Location for events the contract records itself, with nothing known.
+fn unknown_location(env: &Env) -> Location {
+    Location {
+        name: String::from_str(env, ""),
+        facility_id: String::from_str(env, ""),
+        country: String::from_str(env, ""),
+        geohash: String::from_str(env, ""),
+        coordinates: Vec::new(env),
     }
+}
 
-    Ok(())
+/// Hand `product` over to `new_owner`, moving the explicit auth entry along
+/// with it and discarding any outstanding transfer offer.
+fn change_owner(env: &Env, product: &mut Product, new_owner: &Address) {
+    storage::remove_actor_grant(env, &product.id, &product.owner);
+    storage::put_actor_grant(env, &product.id, &owner_grant(new_owner));
+
+    storage::remove_from_index(
+        env,
+        &ProductIndex::Owner(product.owner.clone()),
+        &product.id,
+    );
+    storage::add_to_index(env, &ProductIndex::Owner(new_owner.clone()), &product.id);
+
+    product.owner = new_owner.clone();
+    write_product(env, product);
+    storage::remove_pending_transfer(env, &product.id);
 }
 
-// ─── Contract ────────────────────────────────────────────────────────────────
+/// Add a product to the owner, category and tag indexes.
+fn index_product(env: &Env, product: &Product) {
+    storage::add_to_index(
+        env,
+        &ProductIndex::Owner(product.owner.clone()),
+        &product.id,
+    );
+    storage::add_to_index(
+        env,
+        &ProductIndex::Category(product.category.clone()),
+        &product.id,
+    );
+    for i in 0..product.tags.len() {
+        storage::add_to_index(
+            env,
+            &ProductIndex::Tag(product.tags.get_unchecked(i)),
+            &product.id,
+        );
+    }
+}
 
-#[contract]
-pub struct ChainLogisticsContract;
+/// One page of products from an index, read in index order.
+fn product_page(env: &Env, index: &ProductIndex, offset: u64, limit: u64) -> ProductPage {
+    let total_count = storage::get_index_count(env, index);
 
-#[contractimpl]
-impl ChainLogisticsContract {
-<<<<<<< nnennaokoye/chainlogistics
-    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
-        if storage::has_admin(&env) {
-            return Err(Error::AlreadyInitialized);
+    let ids = storage::get_index_page(env, index, offset, limit);
+    let mut products = Vec::new(env);
+    for i in 0..ids.len() {
+        if let Some(product) = storage::get_product(env, &ids.get_unchecked(i)) {
+            products.push_back(product);
         }
-        admin.require_auth();
-        storage::set_admin(&env, &admin);
-        storage::set_paused(&env, false);
-        Ok(())
     }
 
-    pub fn get_admin(env: Env) -> Result<Address, Error> {
-        storage::get_admin(&env).ok_or(Error::NotInitialized)
+    let has_more = offset.saturating_add(limit) < total_count;
+
+    ProductPage {
+        products,
+        total_count,
+        has_more,
     }
+}
 
-    pub fn is_paused(env: Env) -> bool {
-        storage::is_paused(&env)
+/// The explicit grant stored for a product owner.
+fn owner_grant(owner: &Address) -> ActorGrant {
+    ActorGrant {
+        actor: owner.clone(),
+        role: ActorRole::Manager,
+        valid_from: 0,
+        valid_until: u64::MAX,
     }
+}
 
-    pub fn pause(env: Env, admin: Address) -> Result<(), Error> {
-        require_admin(&env, &admin)?;
-        storage::set_paused(&env, true);
-        Ok(())
+/// Look up an actor's grant, treating grants outside their window as absent.
+fn effective_grant(env: &Env, product_id: &String, actor: &Address) -> Option<ActorGrant> {
+    let grant = storage::get_actor_grant(env, product_id, actor)?;
+    let now = env.ledger().timestamp();
+    if now < grant.valid_from || now > grant.valid_until {
+        return None;
     }
+    Some(grant)
+}
 
-    pub fn unpause(env: Env, admin: Address) -> Result<(), Error> {
-        require_admin(&env, &admin)?;
-        storage::set_paused(&env, false);
-        Ok(())
+/// Hash that links an event to its successor: SHA-256 over the event's XDR.
+fn event_hash(env: &Env, event: &TrackingEvent) -> BytesN<32> {
+    env.crypto().sha256(&event.clone().to_xdr(env))
+}
+
+/// Validate and store a new product owned by `owner`. Callers authorize
+/// `owner` first, so lineage operations can register several products
+/// under a single authorization.
+fn create_product(env: &Env, owner: &Address, config: ProductConfig) -> Result<Product, Error> {
+    require_unpaused(env, PauseScope::Registrations, &config.id, &config.category)?;
+
+    // --- Validation ---
+    const MAX_ID_LEN: u32 = 64;
+    const MAX_CATEGORY_LEN: u32 = 64;
+    const MAX_CERTS: u32 = 50;
+
+    if !validation::non_empty(&config.id) {
+        return Err(Error::InvalidProductId);
+    }
+    if !validation::max_len(&config.id, MAX_ID_LEN) {
+        return Err(Error::InvalidProductId);
+    }
+    let metadata = ProductMetadata {
+        name: config.name.clone(),
+        description: config.description.clone(),
+        tags: config.tags.clone(),
+        media_hashes: config.media_hashes.clone(),
+        custom: config.custom.clone(),
+    };
+    validate_metadata(&metadata)?;
+    if !validation::non_empty(&config.origin.name) {
+        return Err(Error::InvalidOrigin);
+    }
+    validate_location(&config.origin)?;
+    if !validation::non_empty(&config.category) {
+        return Err(Error::InvalidCategory);
+    }
+    if !validation::max_len(&config.category, MAX_CATEGORY_LEN) {
+        return Err(Error::InvalidCategory);
+    }
+    if config.certifications.len() > MAX_CERTS {
+        return Err(Error::TooManyCertifications);
     }
 
-    pub fn transfer_admin(env: Env, admin: Address, new_admin: Address) -> Result<(), Error> {
-        require_admin(&env, &admin)?;
-        new_admin.require_auth();
-        storage::set_admin(&env, &new_admin);
-        Ok(())
+    // --- Duplicate check ---
+    if storage::has_product(env, &config.id) {
+        return Err(Error::ProductAlreadyExists);
     }
 
-    /// Registers a new product and stores it in persistent storage.
-    /// Products persist across contract calls using Soroban's persistent storage API.
-    /// Returns ProductAlreadyExists if the product ID already exists.
-=======
-    // --- Lifecycle Management ---
-    // ═══════════════════════════════════════════════════════════════════════
-    // PRODUCT REGISTRATION
-    // ═══════════════════════════════════════════════════════════════════════
+    let product = Product {
+        id: config.id.clone(),
+        name: config.name,
+        description: config.description,
+        origin: Origin {
+            location: config.origin,
+        },
+        owner: owner.clone(),
+        created_at: env.ledger().timestamp(),
+        active: true,
+        category: config.category,
+        tags: config.tags,
+        certifications: config.certifications,
+        media_hashes: config.media_hashes,
+        custom: config.custom,
+        deactivation_info: Vec::new(env),
+    };
+
+    write_product(env, &product);
+    // Owner is implicitly authorized — store explicit auth entry for lookup convenience
+    storage::put_actor_grant(env, &config.id, &owner_grant(owner));
+    index_product(env, &product);
+    record_version(env, &product, owner, None);
+    let origin = ProductIndex::Origin(product.origin.location.name.clone());
+    storage::add_to_index(env, &origin, &product.id);
+
+    // Update global counters
+    let total = storage::get_total_products(env) + 1;
+    storage::set_total_products(env, total);
+
+    let active = storage::get_active_products(env) + 1;
+    storage::set_active_products(env, active);
+
+    env.events().publish(
+        (Symbol::new(env, "product_registered"), config.id.clone()),
+        product.clone(),
+    );
+
+    Ok(product)
+}
 
-    /// Register a new product.
->>>>>>> main
-    pub fn register_product(
-        env: Env,
-        owner: Address,
-        config: ProductConfig,
-    ) -> Result<Product, Error> {
-<<<<<<< nnennaokoye/chainlogistics
-        require_not_paused(&env)?;
-=======
-        owner.require_auth();
+/// Mark `product` inactive, recording who deactivated it and why.
+fn mark_deactivated(env: &Env, mut product: Product, by: &Address, reason: String) {
+    product.active = false;
+    let mut info = Vec::new(env);
+    info.push_back(DeactInfo {
+        reason: reason.clone(),
+        deactivated_at: env.ledger().timestamp(),
+        deactivated_by: by.clone(),
+    });
+    product.deactivation_info = info;
+
+    write_product(env, &product);
+
+    // Decrement active counter
+    let active = storage::get_active_products(env).saturating_sub(1);
+    storage::set_active_products(env, active);
+
+    env.events().publish(
+        (Symbol::new(env, "product_deactivated"), product.id),
+        (by.clone(), reason),
+    );
+}
 
-        // --- Validation ---
->>>>>>> main
-        const MAX_ID_LEN: u32 = 64;
-        const MAX_NAME_LEN: u32 = 128;
-        const MAX_ORIGIN_LEN: u32 = 128;
-        const MAX_CATEGORY_LEN: u32 = 64;
-        const MAX_DESC_LEN: u32 = 512;
-        const MAX_TAGS: u32 = 20;
-        const MAX_TAG_LEN: u32 = 64;
-        const MAX_CERTS: u32 = 50;
-        const MAX_MEDIA: u32 = 50;
-        const MAX_CUSTOM: u32 = 20;
-        const MAX_CUSTOM_VAL_LEN: u32 = 256;
+// ─── Event Merkle tree ───────────────────────────────────────────────────────
+//
+// RFC 9162 hashing: leaves are prefixed with 0x00 and interior nodes with
+// 0x01, so a leaf can never be passed off as a node. `chainlogistics-merkle`
+// builds inclusion proofs against the same tree on the host.
 
-        if !validation::non_empty(&config.id) {
-            return Err(Error::InvalidProductId);
-        }
-        if !validation::max_len(&config.id, MAX_ID_LEN) {
-            return Err(Error::ProductIdTooLong);
-        }
-        if !validation::non_empty(&config.name) {
-            return Err(Error::InvalidProductName);
-        }
-        if !validation::max_len(&config.name, MAX_NAME_LEN) {
-            return Err(Error::ProductNameTooLong);
-        }
-        if !validation::non_empty(&config.origin_location) {
-            return Err(Error::InvalidOrigin);
-        }
-        if !validation::max_len(&config.origin_location, MAX_ORIGIN_LEN) {
-            return Err(Error::OriginTooLong);
+fn merkle_leaf(env: &Env, event_hash: &BytesN<32>) -> BytesN<32> {
+    let mut data = Bytes::from_array(env, &[0u8]);
+    data.extend_from_array(&event_hash.to_array());
+    env.crypto().sha256(&data)
+}
+
+fn merkle_node(env: &Env, left: &BytesN<32>, right: &BytesN<32>) -> BytesN<32> {
+    let mut data = Bytes::from_array(env, &[1u8]);
+    data.extend_from_array(&left.to_array());
+    data.extend_from_array(&right.to_array());
+    env.crypto().sha256(&data)
+}
+
+/// Root of the empty tree: SHA-256 of the empty string.
+fn merkle_empty_root(env: &Env) -> BytesN<32> {
+    env.crypto().sha256(&Bytes::new(env))
+}
+
+/// Fold the peaks, smallest first, into the root.
+fn merkle_root(env: &Env, peaks: &Vec<BytesN<32>>) -> BytesN<32> {
+    let mut root = match peaks.last() {
+        Some(peak) => peak,
+        None => return merkle_empty_root(env),
+    };
+    for i in (0..peaks.len() - 1).rev() {
+        root = merkle_node(env, &peaks.get_unchecked(i), &root);
+    }
+    root
+}
+
+/// Add an event hash as the next leaf of the product's Merkle tree.
+fn append_merkle_leaf(env: &Env, product_id: &String, event_hash: &BytesN<32>) {
+    let mut tree = storage::get_event_merkle(env, product_id).unwrap_or(EventMerkleTree {
+        size: 0,
+        root: merkle_empty_root(env),
+        peaks: Vec::new(env),
+    });
+
+    // Each set low bit of the old size is a peak the new leaf completes.
+    let mut node = merkle_leaf(env, event_hash);
+    let mut size = tree.size;
+    while size & 1 == 1 {
+        let left = tree.peaks.pop_back_unchecked();
+        node = merkle_node(env, &left, &node);
+        size >>= 1;
+    }
+    tree.peaks.push_back(node);
+    tree.size += 1;
+    tree.root = merkle_root(env, &tree.peaks);
+
+    storage::put_event_merkle(env, product_id, &tree);
+    storage::put_event_merkle_root_at(env, product_id, tree.size, &tree.root);
+}
+
+/// Store a new event for a product and update every per-product index.
+///
+/// Callers are responsible for permission and input checks.
+fn append_event(
+    env: &Env,
+    product_id: &String,
+    actor: Address,
+    location: Location,
+    event_type: Symbol,
+    data_hash: BytesN<32>,
+    note: String,
+    metadata: Map<Symbol, String>,
+) -> TrackingEvent {
+    let prev_hash = storage::get_event_chain_head(env, product_id)
+        .unwrap_or(BytesN::from_array(env, &[0u8; 32]));
+
+    let event_id = storage::next_event_id(env);
+    let event = TrackingEvent {
+        event_id,
+        product_id: product_id.clone(),
+        actor,
+        timestamp: env.ledger().timestamp(),
+        location,
+        event_type,
+        data_hash,
+        note,
+        metadata,
+        prev_hash,
+    };
+
+    storage::put_event(env, &event);
+    let hash = event_hash(env, &event);
+    storage::put_event_chain_head(env, product_id, &hash);
+    append_merkle_leaf(env, product_id, &hash);
+
+    // Append to the product's ordered event list
+    storage::push_product_event(env, product_id, event_id, event.timestamp);
+
+    // Index by event type for efficient type-based filtering
+    storage::index_event_by_type(env, product_id, &event.event_type, event_id);
+    storage::index_event_by_actor(env, product_id, &event.actor, event_id);
+
+    env.events().publish(
+        (
+            Symbol::new(env, "tracking_event"),
+            product_id.clone(),
+            event_id,
+        ),
+        event.clone(),
+    );
+
+    event
+}
+
+// ─── Event queries ───────────────────────────────────────────────────────────
+
+/// Whether `event` passes a composite filter. Empty fields match anything.
+fn event_matches_filter(env: &Env, filter: &TrackingEventFilter, event: &TrackingEvent) -> bool {
+    let empty_sym = Symbol::new(env, "");
+    let empty_loc = String::from_str(env, "");
+
+    if filter.event_type != empty_sym && event.event_type != filter.event_type {
+        return false;
+    }
+    if filter.start_time > 0 && event.timestamp < filter.start_time {
+        return false;
+    }
+    if filter.end_time < u64::MAX && event.timestamp > filter.end_time {
+        return false;
+    }
+    if filter.location != empty_loc && event.location.name != filter.location {
+        return false;
+    }
+    if filter.country != empty_loc && event.location.country != filter.country {
+        return false;
+    }
+    if filter.facility_id != empty_loc && event.location.facility_id != filter.facility_id {
+        return false;
+    }
+    if filter.geohash_prefix != empty_loc
+        && !validation::has_prefix(&event.location.geohash, &filter.geohash_prefix)
+    {
+        return false;
+    }
+    true
+}
+
+fn query_matches(env: &Env, query: &EventQuery, event: &TrackingEvent) -> bool {
+    match query {
+        EventQuery::All
+        | EventQuery::ByType(_)
+        | EventQuery::ByActor(_)
+        | EventQuery::ActorAcrossProducts(_) => true,
+        EventQuery::TimeRange(start, end) => event.timestamp >= *start && event.timestamp <= *end,
+        EventQuery::Filtered(filter) => event_matches_filter(env, filter, event),
+    }
+}
+
+/// Length of the event ID list `query` walks.
+fn query_list_len(env: &Env, product_id: &String, query: &EventQuery) -> u64 {
+    match query {
+        EventQuery::ByType(event_type) => {
+            storage::get_event_count_by_type(env, product_id, event_type)
         }
-        if !validation::non_empty(&config.category) {
-            return Err(Error::InvalidCategory);
+        EventQuery::ByActor(actor) => {
+            storage::get_product_event_count_by_actor(env, product_id, actor)
         }
-        if !validation::max_len(&config.category, MAX_CATEGORY_LEN) {
-            return Err(Error::CategoryTooLong);
+        EventQuery::ActorAcrossProducts(actor) => storage::get_event_count_by_actor(env, actor),
+        _ => storage::get_product_event_count(env, product_id),
+    }
+}
+
+/// IDs at positions `from..to` of the list `query` walks.
+fn query_list_ids(
+    env: &Env,
+    product_id: &String,
+    query: &EventQuery,
+    from: u64,
+    to: u64,
+) -> Vec<u64> {
+    match query {
+        EventQuery::ByType(event_type) => {
+            storage::get_event_ids_by_type(env, product_id, event_type, from, to - from)
         }
-        if !validation::max_len(&config.description, MAX_DESC_LEN) {
-            return Err(Error::DescriptionTooLong);
+        EventQuery::ByActor(actor) => {
+            storage::get_product_event_ids_by_actor(env, product_id, actor, from, to - from)
         }
-        if config.tags.len() > MAX_TAGS {
-            return Err(Error::TooManyTags);
+        EventQuery::ActorAcrossProducts(actor) => {
+            storage::get_event_ids_by_actor(env, actor, from, to - from)
         }
-        for i in 0..config.tags.len() {
-            if !validation::max_len(&config.tags.get_unchecked(i), MAX_TAG_LEN) {
-                return Err(Error::TagTooLong);
+        _ => storage::get_product_event_ids_paginated(env, product_id, from, to - from),
+    }
+}
+
+/// Scan the cursor's list from its position, skipping `skip` matches and
+/// then collecting up to `limit`. IDs are read a page at a time and the scan
+/// stops at the first match past the limit, so a page never walks the
+/// whole history. Also returns how many matches were skipped.
+fn read_event_page(
+    env: &Env,
+    mut cursor: EventCursor,
+    skip: u64,
+    limit: u64,
+) -> (TrackingEventPage, u64) {
+    let mut events = Vec::new(env);
+    let mut position = cursor.position;
+    let mut has_more = false;
+    let mut skipped = 0u64;
+
+    'scan: while position < cursor.end {
+        let to = (position + storage::EVENT_ID_PAGE_SIZE).min(cursor.end);
+        let ids = query_list_ids(env, &cursor.product_id, &cursor.query, position, to);
+        for (i, eid) in ids.iter().enumerate() {
+            let Some(event) = storage::get_event(env, eid) else {
+                continue;
+            };
+            if !query_matches(env, &cursor.query, &event) {
+                continue;
             }
-        }
-        if config.certifications.len() > MAX_CERTS {
-            return Err(Error::TooManyCertifications);
-        }
-        if config.media_hashes.len() > MAX_MEDIA {
-            return Err(Error::TooManyMediaHashes);
-        }
-        if config.custom.len() > MAX_CUSTOM {
-            return Err(Error::TooManyCustomFields);
-        }
-        let custom_keys = config.custom.keys();
-        for i in 0..custom_keys.len() {
-            let k = custom_keys.get_unchecked(i);
-            let v = config.custom.get_unchecked(k);
-            if !validation::max_len(&v, MAX_CUSTOM_VAL_LEN) {
-                return Err(Error::CustomFieldValueTooLong);
+            if skipped == skip && events.len() as u64 == limit {
+                has_more = true;
+                break 'scan;
+            }
+            cursor.position = position + i as u64 + 1;
+            cursor.last_event_id = eid;
+            if skipped < skip {
+                skipped += 1;
+            } else {
+                events.push_back(event);
             }
         }
+        position = to;
+    }
 
-        // --- Duplicate check ---
-        if storage::has_product(&env, &config.id) {
-            return Err(Error::ProductAlreadyExists);
-        }
-        if !validation::max_len(&description, MAX_DESCRIPTION_LEN) {
-            return Err(Error::DescriptionTooLong);
-        }
+    let page = TrackingEventPage {
+        events,
+        total_count: cursor.total_count,
+        total_is_estimate: matches!(cursor.query, EventQuery::Filtered(_)),
+        has_more,
+        next_cursor: if has_more {
+            cursor.to_xdr(env)
+        } else {
+            Bytes::new(env)
+        },
+    };
+    (page, skipped)
+}
 
-        if tags.len() > MAX_TAGS {
-            return Err(Error::TooManyTags);
-        }
-        for i in 0..tags.len() {
-            let t = tags.get_unchecked(i);
-            if !validation::max_len(&t, MAX_TAG_LEN) {
-                return Err(Error::TagTooLong);
-            }
+/// First page of `query`, starting `offset` matches in.
+fn first_event_page(
+    env: &Env,
+    product_id: String,
+    query: EventQuery,
+    offset: u64,
+    limit: u64,
+) -> TrackingEventPage {
+    // Time bounds narrow the walk to the events recorded between them,
+    // found by binary search over the product's timestamp pages
+    let (from, end) = match &query {
+        EventQuery::TimeRange(start, end) => {
+            storage::find_product_events_in_time_range(env, &product_id, *start, *end)
         }
+        EventQuery::Filtered(filter) => storage::find_product_events_in_time_range(
+            env,
+            &product_id,
+            filter.start_time,
+            filter.end_time,
+        ),
+        _ => (0, query_list_len(env, &product_id, &query)),
+    };
+    // Counting a filter's matches would mean reading every event in range,
+    // so its total starts as the size of the range
+    let (position, skip) = match query {
+        EventQuery::Filtered(_) => (from, offset),
+        // Every event in range matches, so the offset is a position
+        _ => (from + offset.min(end - from), 0),
+    };
+
+    let last_event_id = match position {
+        0 => 0,
+        _ => query_list_ids(env, &product_id, &query, position - 1, position)
+            .get(0)
+            .unwrap_or(0),
+    };
+    let cursor = EventCursor {
+        product_id,
+        query,
+        position,
+        end,
+        total_count: end - from,
+        last_event_id,
+    };
+    let (mut page, skipped) = read_event_page(env, cursor, skip, limit);
+
+    // A first page that reached the end of the range has seen every match
+    if page.total_is_estimate && !page.has_more {
+        page.total_count = skipped + page.events.len() as u64;
+        page.total_is_estimate = false;
+    }
+    page
+}
+
+/// Decode a cursor from `get_next_events`, checking it still lines up with
+/// the product's event lists. Bytes that are not XDR at all trap in the
+/// host; well-formed XDR of the wrong shape is reported as `InvalidCursor`.
+fn read_event_cursor(env: &Env, bytes: &Bytes) -> Result<EventCursor, Error> {
+    // The empty `next_cursor` of a last page
+    if bytes.is_empty() {
+        return Err(Error::InvalidCursor);
+    }
+    let cursor = EventCursor::from_xdr(env, bytes).map_err(|_| Error::InvalidCursor)?;
+    if !matches!(cursor.query, EventQuery::ActorAcrossProducts(_)) {
+        let _ = read_product(env, &cursor.product_id)?;
+    }
+
+    let len = query_list_len(env, &cursor.product_id, &cursor.query);
+    if cursor.position > cursor.end || cursor.end > len {
+        return Err(Error::InvalidCursor);
+    }
+    let last = match cursor.position {
+        0 => 0,
+        p => query_list_ids(env, &cursor.product_id, &cursor.query, p - 1, p)
+            .get(0)
+            .unwrap_or(0),
+    };
+    if last != cursor.last_event_id {
+        return Err(Error::InvalidCursor);
+    }
+    Ok(cursor)
+}
+
+/// Tracking-event type recorded on both sides of a lineage operation.
+fn lineage_event_type(env: &Env, op: &LineageOp) -> Symbol {
+    match op {
+        LineageOp::Split => Symbol::new(env, "split"),
+        LineageOp::Merge => Symbol::new(env, "merged"),
+        LineageOp::Transform => Symbol::new(env, "transformed"),
+    }
+}
 
-        if certifications.len() > MAX_CERTIFICATIONS {
-            return Err(Error::TooManyCertifications);
+/// Authorize the caller once, then read every parent of a lineage operation,
+/// checking the caller owns it and that it can still be consumed.
+fn read_lineage_parents(
+    env: &Env,
+    owner: &Address,
+    parent_ids: &Vec<String>,
+) -> Result<Vec<Product>, Error> {
+    owner.require_auth();
+    let mut parents = Vec::new(env);
+    for i in 0..parent_ids.len() {
+        let parent = read_product(env, &parent_ids.get_unchecked(i))?;
+        if &parent.owner != owner {
+            return Err(Error::Unauthorized);
         }
-        if media_hashes.len() > MAX_MEDIA_HASHES {
-            return Err(Error::TooManyMediaHashes);
+        require_product_unpaused(env, PauseScope::All, &parent)?;
+        if !parent.active {
+            return Err(Error::ProductDeactivated);
         }
+        parents.push_back(parent);
+    }
+    Ok(parents)
+}
 
-        if custom.len() > MAX_CUSTOM_FIELDS {
-            return Err(Error::TooManyCustomFields);
+/// Link freshly registered `children` to `parent_ids`, log lineage events on
+/// both sides and deactivate the consumed parents.
+fn record_lineage(
+    env: &Env,
+    owner: &Address,
+    op: LineageOp,
+    parent_ids: &Vec<String>,
+    children: &Vec<Product>,
+) -> Result<(), Error> {
+    let event_type = lineage_event_type(env, &op);
+    let no_data = BytesN::from_array(env, &[0u8; 32]);
+    let record = LineageRecord {
+        op: op.clone(),
+        parents: parent_ids.clone(),
+        created_at: env.ledger().timestamp(),
+    };
+
+    for i in 0..children.len() {
+        let child_id = children.get_unchecked(i).id;
+        storage::put_lineage_record(env, &child_id, &record);
+        for j in 0..parent_ids.len() {
+            storage::add_product_child(env, &parent_ids.get_unchecked(j), &child_id);
         }
-        let custom_keys = custom.keys();
-        for i in 0..custom_keys.len() {
-            let k = custom_keys.get_unchecked(i);
-            let v = custom.get_unchecked(k);
-            if !validation::max_len(&v, MAX_CUSTOM_VALUE_LEN) {
-                return Err(Error::CustomFieldValueTooLong);
+        append_event(
+            env,
+            &child_id,
+            owner.clone(),
+            unknown_location(env),
+            event_type.clone(),
+            no_data.clone(),
+            String::from_str(env, "Created from parent products"),
+            Map::new(env),
+        );
+    }
+
+    for i in 0..parent_ids.len() {
+        let parent_id = parent_ids.get_unchecked(i);
+        append_event(
+            env,
+            &parent_id,
+            owner.clone(),
+            unknown_location(env),
+            event_type.clone(),
+            no_data.clone(),
+            String::from_str(env, "Consumed into child products"),
+            Map::new(env),
+        );
+        mark_deactivated(
+            env,
+            read_product(env, &parent_id)?,
+            owner,
+            String::from_str(env, "Consumed by lineage operation"),
+        );
+    }
+
+    Ok(())
+}
+
+/// Breadth-first walk of lineage links up to `depth` hops. `upward` follows
+/// parents, otherwise children.
+fn walk_lineage(env: &Env, product_id: &String, depth: u32, upward: bool) -> Vec<LineageEntry> {
+    let mut entries: Vec<LineageEntry> = Vec::new(env);
+    let mut seen = Vec::new(env);
+    seen.push_back(product_id.clone());
+
+    let mut frontier = Vec::new(env);
+    frontier.push_back(product_id.clone());
+
+    for d in 1..=depth {
+        let mut next = Vec::new(env);
+        for i in 0..frontier.len() {
+            let current = frontier.get_unchecked(i);
+            let related = if upward {
+                match storage::get_lineage_record(env, &current) {
+                    Some(record) => record.parents,
+                    None => Vec::new(env),
+                }
+            } else {
+                storage::get_product_children(env, &current)
+            };
+
+            for j in 0..related.len() {
+                let id = related.get_unchecked(j);
+                if seen.contains(&id) {
+                    continue;
+                }
+                // The link's operation is always the one recorded on its child
+                let child = if upward { current.clone() } else { id.clone() };
+                if let Some(record) = storage::get_lineage_record(env, &child) {
+                    entries.push_back(LineageEntry {
+                        product_id: id.clone(),
+                        depth: d,
+                        op: record.op,
+                    });
+                }
+                seen.push_back(id.clone());
+                next.push_back(id);
             }
         }
-
-        if storage::has_product(&env, &id) {
-            return Err(Error::ProductAlreadyExists);
+        if next.is_empty() {
+            break;
         }
+        frontier = next;
+    }
 
-        owner.require_auth();
-
-        let product = Product {
-            id: config.id.clone(),
-            name: config.name,
-            description: config.description,
-            origin: Origin {
-                location: config.origin_location,
-            },
-            owner: owner.clone(),
-            created_at: env.ledger().timestamp(),
-            active: true,
-            category: config.category,
-            tags: config.tags,
-            certifications: config.certifications,
-            media_hashes: config.media_hashes,
-            custom: config.custom,
-            deactivation_info: Vec::new(&env),
-        };
+    entries
+}
 
-        write_product(&env, &product);
-        storage::put_product_event_ids(&env, &config.id, &Vec::new(&env));
-        // Owner is implicitly authorized — store explicit auth entry for lookup convenience
-        storage::set_auth(&env, &config.id, &owner, true);
+fn is_lifecycle_state(lifecycle: &Lifecycle, event_type: &Symbol) -> bool {
+    if lifecycle.initial.contains(event_type) {
+        return true;
+    }
+    for i in 0..lifecycle.transitions.len() {
+        let t = lifecycle.transitions.get_unchecked(i);
+        if &t.from == event_type || &t.to == event_type {
+            return true;
+        }
+    }
+    false
+}
 
-        // Update global counters
-        let total = storage::get_total_products(&env) + 1;
-        storage::set_total_products(&env, total);
+/// Validate and append one event signed by `actor`, whose signature the
+/// caller has already required.
+fn record_event(env: &Env, actor: &Address, input: TrackingEventInput) -> Result<u64, Error> {
+    const MAX_METADATA_FIELDS: u32 = 20;
+    const MAX_METADATA_VALUE_LEN: u32 = 256;
 
-        let active = storage::get_active_products(&env) + 1;
-        storage::set_active_products(&env, active);
+    let product = read_product(env, &input.product_id)?;
+    require_product_unpaused(env, PauseScope::EventWrites, &product)?;
+    check_can_add_event(env, &input.product_id, &product, actor, &input.event_type)?;
 
-        env.events().publish(
-            (Symbol::new(&env, "product_registered"), config.id.clone()),
-            product.clone(),
-        );
+    // Validate metadata limits
+    if input.metadata.len() > MAX_METADATA_FIELDS {
+        return Err(Error::TooManyCustomFields);
+    }
 
-        Ok(product)
+    let meta_keys = input.metadata.keys();
+    for i in 0..meta_keys.len() {
+        let k = meta_keys.get_unchecked(i);
+        let v = input.metadata.get_unchecked(k);
+        if !validation::max_len(&v, MAX_METADATA_VALUE_LEN) {
+            return Err(Error::CustomFieldValueTooLong);
+        }
     }
 
-    // ═══════════════════════════════════════════════════════════════════════
-    // PRODUCT LIFECYCLE — DEACTIVATION & REACTIVATION
-    // ═══════════════════════════════════════════════════════════════════════
+    validate_location(&input.location)?;
+    advance_state(env, &product, &input.event_type)?;
+
+    let event = append_event(
+        env,
+        &input.product_id,
+        actor.clone(),
+        input.location,
+        input.event_type,
+        input.data_hash,
+        input.note,
+        input.metadata,
+    );
+    Ok(event.event_id)
+}
 
-    /// Deactivate a product.
-    pub fn deactivate_product(
-        env: Env,
-        owner: Address,
-        product_id: String,
-        reason: String,
-    ) -> Result<(), Error> {
-        let mut product = read_product(&env, &product_id)?;
-        require_owner(&product, &owner)?;
+/// Check `event_type` against the product category's lifecycle and, if it is
+/// a state event, record it as the product's current state.
+fn advance_state(env: &Env, product: &Product, event_type: &Symbol) -> Result<(), Error> {
+    let lifecycle = match storage::get_category_lifecycle(env, &product.category) {
+        Some(lifecycle) => lifecycle,
+        None => return Ok(()),
+    };
 
-        if !product.active {
-            return Err(Error::ProductDeactivated);
-        }
+    if !is_lifecycle_state(&lifecycle, event_type) {
+        return Ok(());
+    }
 
-        if !validation::non_empty(&reason) {
-            return Err(Error::DeactivationReasonRequired);
+    let allowed = match storage::get_product_state(env, &product.id) {
+        None => lifecycle.initial.contains(event_type),
+        Some(current) => {
+            let mut found = false;
+            for i in 0..lifecycle.transitions.len() {
+                let t = lifecycle.transitions.get_unchecked(i);
+                if t.from == current && &t.to == event_type {
+                    found = true;
+                    break;
+                }
+            }
+            found
         }
+    };
 
-        product.active = false;
-        let mut info = Vec::new(&env);
-        info.push_back(DeactInfo {
-            reason: reason.clone(),
-            deactivated_at: env.ledger().timestamp(),
-            deactivated_by: owner.clone(),
-        });
-        product.deactivation_info = info;
-
-        write_product(&env, &product);
+    if !allowed {
+        return Err(Error::InvalidStateTransition);
+    }
 
-        // Decrement active counter
-        let active = storage::get_active_products(&env).saturating_sub(1);
-        storage::set_active_products(&env, active);
+    storage::put_product_state(env, &product.id, event_type);
+    Ok(())
+}
 
-        env.events().publish(
-            (Symbol::new(&env, "product_deactivated"), product_id.clone()),
-            (owner, reason),
-        );
+/// XDR of `(network_id, contract, body)`, the message a device signs.
+/// Naming the network and contract keeps a signature made for one
+/// deployment from being replayed against another.
+fn device_payload<T: IntoVal<Env, Val>>(env: &Env, body: T) -> Bytes {
+    let body: Val = body.into_val(env);
+    (
+        env.ledger().network_id(),
+        env.current_contract_address(),
+        body,
+    )
+        .to_xdr(env)
+}
 
-        Ok(())
+/// Load a registered device and check it may report for `product_id`.
+fn read_device_in_scope(env: &Env, key: &BytesN<32>, product_id: &String) -> Result<Device, Error> {
+    let device = storage::get_device(env, key).ok_or(Error::DeviceNotRegistered)?;
+    if !device.active {
+        return Err(Error::DeviceRevoked);
+    }
+    if !device.scope.contains(product_id) {
+        return Err(Error::DeviceOutOfScope);
+    }
+    Ok(device)
+}
+
+/// Verify a device signature over `payload` and enforce that `recorded_at`
+/// is newer than the device's previous submission, which stops replays.
+fn verify_device_payload(
+    env: &Env,
+    key: &BytesN<32>,
+    payload: &Bytes,
+    signature: &BytesN<64>,
+    recorded_at: u64,
+) -> Result<(), Error> {
+    env.crypto().ed25519_verify(key, payload, signature);
+
+    if recorded_at <= storage::get_device_last_reading(env, key) {
+        return Err(Error::StaleReading);
+    }
+    storage::set_device_last_reading(env, key, recorded_at);
+    Ok(())
+}
+
+/// Whether a reading falls outside a product's configured thresholds.
+fn is_excursion(reading: &TelemetryReading, thresholds: &TelemetryThresholds) -> bool {
+    reading.temperature < thresholds.min_temperature
+        || reading.temperature > thresholds.max_temperature
+        || reading.humidity > thresholds.max_humidity
+        || reading.shock > thresholds.max_shock
+}
+
+/// Event types still accepted on a recalled product.
+fn recall_allowed_event_types(env: &Env) -> Vec<Symbol> {
+    let mut event_types = Vec::new(env);
+    event_types.push_back(Symbol::new(env, "returned"));
+    event_types.push_back(Symbol::new(env, "disposed"));
+    event_types
+}
+
+/// Products in `index` to seed a recall, refusing indexes of more than `max`
+/// products before any are loaded.
+fn index_recall_seeds(env: &Env, index: &ProductIndex, max: u32) -> Result<Vec<String>, Error> {
+    if storage::get_index_count(env, index) > max as u64 {
+        return Err(Error::InvalidInput);
+    }
+    Ok(storage::get_index_page(env, index, 0, max as u64))
+}
+
+/// Expand recall seed products with their lineage descendants and siblings.
+fn collect_recall_targets(env: &Env, seeds: &Vec<String>) -> Vec<String> {
+    const MAX_RECALL_DEPTH: u32 = 10;
+
+    let mut affected: Vec<String> = Vec::new(env);
+    for i in 0..seeds.len() {
+        let seed = seeds.get_unchecked(i);
+        if !affected.contains(&seed) {
+            affected.push_back(seed.clone());
+        }
+
+        let descendants = walk_lineage(env, &seed, MAX_RECALL_DEPTH, false);
+        for j in 0..descendants.len() {
+            let id = descendants.get_unchecked(j).product_id;
+            if !affected.contains(&id) {
+                affected.push_back(id);
+            }
+        }
+
+        if let Some(record) = storage::get_lineage_record(env, &seed) {
+            for j in 0..record.parents.len() {
+                let siblings = storage::get_product_children(env, &record.parents.get_unchecked(j));
+                for k in 0..siblings.len() {
+                    let id = siblings.get_unchecked(k);
+                    if !affected.contains(&id) {
+                        affected.push_back(id);
+                    }
+                }
+            }
+        }
+    }
+    affected
+}
+
+/// Record a recall of `scope` and flag every affected product. Category and
+/// origin scopes need `is_admin`; explicit products need it or ownership.
+fn start_recall(
+    env: &Env,
+    initiator: &Address,
+    is_admin: bool,
+    scope: RecallScope,
+    reason: String,
+    severity: RecallSeverity,
+) -> Result<Recall, Error> {
+    const MAX_RECALL_PRODUCTS: u32 = 200;
+    const MAX_REASON_LEN: u32 = 256;
+
+    if !validation::non_empty(&reason) || !validation::max_len(&reason, MAX_REASON_LEN) {
+        return Err(Error::InvalidInput);
+    }
+
+    let seeds = match &scope {
+        RecallScope::Products(ids) => {
+            for i in 0..ids.len() {
+                let product = read_product(env, &ids.get_unchecked(i))?;
+                if !is_admin && product.owner != *initiator {
+                    return Err(Error::Unauthorized);
+                }
+            }
+            ids.clone()
+        }
+        RecallScope::Category(category) => {
+            if !is_admin {
+                return Err(Error::Unauthorized);
+            }
+            index_recall_seeds(
+                env,
+                &ProductIndex::Category(category.clone()),
+                MAX_RECALL_PRODUCTS,
+            )?
+        }
+        RecallScope::Origin(origin) => {
+            if !is_admin {
+                return Err(Error::Unauthorized);
+            }
+            index_recall_seeds(
+                env,
+                &ProductIndex::Origin(origin.clone()),
+                MAX_RECALL_PRODUCTS,
+            )?
+        }
+    };
+
+    let affected = collect_recall_targets(env, &seeds);
+    if affected.is_empty() {
+        return Err(Error::RecallScopeEmpty);
+    }
+    if affected.len() > MAX_RECALL_PRODUCTS {
+        return Err(Error::InvalidInput);
+    }
+
+    let recall = Recall {
+        recall_id: storage::next_recall_id(env),
+        initiator: initiator.clone(),
+        scope,
+        reason: reason.clone(),
+        severity,
+        initiated_at: env.ledger().timestamp(),
+        affected: affected.clone(),
+    };
+    storage::put_recall(env, &recall);
+
+    let event_type = Symbol::new(env, "recalled");
+    let no_data = BytesN::from_array(env, &[0u8; 32]);
+    for i in 0..affected.len() {
+        let product_id = affected.get_unchecked(i);
+        storage::set_product_recall(env, &product_id, recall.recall_id);
+        append_event(
+            env,
+            &product_id,
+            initiator.clone(),
+            unknown_location(env),
+            event_type.clone(),
+            no_data.clone(),
+            reason.clone(),
+            Map::new(env),
+        );
+    }
+
+    env.events().publish(
+        (Symbol::new(env, "recall_initiated"), recall.recall_id),
+        recall.clone(),
+    );
+
+    Ok(recall)
+}
+
+/// All grants on a product that are in effect right now, in index order.
+fn effective_grants(env: &Env, product_id: &String) -> Vec<ActorGrant> {
+    let actors = storage::get_product_actors(env, product_id);
+    let mut grants = Vec::new(env);
+    for i in 0..actors.len() {
+        if let Some(grant) = effective_grant(env, product_id, &actors.get_unchecked(i)) {
+            grants.push_back(grant);
+        }
+    }
+    grants
+}
+
+/// Event types a role may log when the owner has not configured its own list.
+fn default_role_permissions(env: &Env, role: &ActorRole) -> Vec<Symbol> {
+    let names: &[&str] = match role {
+        ActorRole::Manager => &[],
+        ActorRole::Carrier => &["shipped", "in_transit", "delivered"],
+        ActorRole::Warehouse => &["received", "stored", "dispatched"],
+        ActorRole::Inspector => &["inspected", "certified", "rejected"],
+        ActorRole::Retailer => &["received", "sold", "returned"],
+    };
+
+    let mut event_types = Vec::new(env);
+    for name in names {
+        event_types.push_back(Symbol::new(env, name));
+    }
+    event_types
+}
+
+fn role_permissions(env: &Env, product_id: &String, role: &ActorRole) -> Vec<Symbol> {
+    storage::get_role_permissions(env, product_id, role)
+        .unwrap_or_else(|| default_role_permissions(env, role))
+}
+
+/// Verify that an actor is allowed to add events:
+///  - product must be active
+///  - caller must be the owner or an explicitly authorized actor
+///  - the actor's role must allow `event_type`
+///
+/// Callers require the actor's signature themselves, or a device's on the
+/// actor's behalf.
+fn check_can_add_event(
+    env: &Env,
+    product_id: &String,
+    product: &Product,
+    caller: &Address,
+    event_type: &Symbol,
+) -> Result<(), Error> {
+    // Deactivated products reject all new events
+    if !product.active {
+        return Err(Error::ProductDeactivated);
+    }
+
+    // Recalled products only accept return and disposal events
+    if storage::get_product_recall(env, product_id).is_some()
+        && !recall_allowed_event_types(env).contains(event_type)
+    {
+        return Err(Error::ProductRecalled);
+    }
+
+    // Owner always has permission
+    if &product.owner == caller {
+        return Ok(());
+    }
+
+    // Check explicit authorization
+    let grant = effective_grant(env, product_id, caller).ok_or(Error::Unauthorized)?;
+    if grant.role == ActorRole::Manager {
+        return Ok(());
+    }
+
+    if !role_permissions(env, product_id, &grant.role).contains(event_type) {
+        return Err(Error::EventTypeNotPermitted);
+    }
+    Ok(())
+}
+
+// ─── Emergency controls ──────────────────────────────────────────────────────
+
+/// Whether a pause on `scope` is in force now.
+fn is_scope_paused(env: &Env, scope: &PauseScope) -> bool {
+    match storage::get_pause(env, scope) {
+        Some(pause) => pause.expires_at == 0 || env.ledger().timestamp() < pause.expires_at,
+        None => false,
+    }
+}
+
+/// Fail with `ContractPaused` when a write of this `kind` to the product is
+/// blocked: by a global pause, a pause on the kind of write, or a pause on
+/// the product or its category. Pass `PauseScope::All` as the kind for
+/// writes that only the product and category scopes single out.
+fn require_unpaused(
+    env: &Env,
+    kind: PauseScope,
+    product_id: &String,
+    category: &String,
+) -> Result<(), Error> {
+    let scopes = [
+        PauseScope::All,
+        kind,
+        PauseScope::Product(product_id.clone()),
+        PauseScope::Category(category.clone()),
+    ];
+    if scopes.iter().any(|scope| is_scope_paused(env, scope)) {
+        return Err(Error::ContractPaused);
+    }
+    Ok(())
+}
+
+fn require_product_unpaused(env: &Env, kind: PauseScope, product: &Product) -> Result<(), Error> {
+    require_unpaused(env, kind, &product.id, &product.category)
+}
+
+/// Store a pause on `scope` lasting `duration` seconds (0 = until unpaused).
+fn record_pause(
+    env: &Env,
+    by: &Address,
+    scope: PauseScope,
+    reason: String,
+    duration: u64,
+) -> PauseRecord {
+    // Drop lapsed pauses so the scope list only holds ones that matter
+    for stale in storage::get_pause_scopes(env).iter() {
+        if !is_scope_paused(env, &stale) {
+            storage::remove_pause(env, &stale);
+        }
+    }
+
+    let now = env.ledger().timestamp();
+    let pause = PauseRecord {
+        scope,
+        reason,
+        paused_by: by.clone(),
+        paused_at: now,
+        expires_at: match duration {
+            0 => 0,
+            d => now.saturating_add(d),
+        },
+    };
+    storage::put_pause(env, &pause);
+    env.events()
+        .publish((Symbol::new(env, "paused"), by.clone()), pause.clone());
+    pause
+}
+
+// ─── Admin council ───────────────────────────────────────────────────────────
+
+/// Seconds a council proposal stays open for approval and execution.
+pub const PROPOSAL_LIFETIME: u64 = 7 * 24 * 60 * 60;
+
+/// Seconds between a timelocked proposal being opened and running.
+pub const COUNCIL_TIMELOCK: u64 = 2 * 24 * 60 * 60;
+
+/// Whether `caller` is the admin and no council has taken over.
+fn is_sole_admin(env: &Env, caller: &Address) -> bool {
+    storage::get_admin_council(env).is_none() && storage::get_admin(env) == Some(caller.clone())
+}
+
+fn validate_pause_reason(reason: &String) -> Result<(), Error> {
+    const MAX_REASON_LEN: u32 = 256;
+
+    if !validation::non_empty(reason) || !validation::max_len(reason, MAX_REASON_LEN) {
+        return Err(Error::InvalidInput);
+    }
+    Ok(())
+}
+
+fn validate_council(council: &AdminCouncil) -> Result<(), Error> {
+    const MAX_SIGNERS: u32 = 20;
+
+    let signers = &council.signers;
+    if signers.is_empty() || signers.len() > MAX_SIGNERS {
+        return Err(Error::InvalidInput);
+    }
+    if council.threshold == 0 || council.threshold > signers.len() {
+        return Err(Error::InvalidInput);
+    }
+    for i in 1..signers.len() {
+        if signers.slice(0..i).contains(signers.get_unchecked(i)) {
+            return Err(Error::InvalidInput);
+        }
+    }
+    Ok(())
+}
+
+/// The council, after checking `signer` authorized the call and sits on it.
+fn require_council_signer(env: &Env, signer: &Address) -> Result<AdminCouncil, Error> {
+    let council = storage::get_admin_council(env).ok_or(Error::NotInitialized)?;
+    signer.require_auth();
+    if !council.signers.contains(signer) {
+        return Err(Error::Unauthorized);
+    }
+    Ok(council)
+}
+
+fn read_open_proposal(env: &Env, proposal_id: u64) -> Result<AdminProposal, Error> {
+    let proposal = storage::get_admin_proposal(env, proposal_id).ok_or(Error::ProposalNotFound)?;
+    if proposal.status != ProposalStatus::Pending || env.ledger().timestamp() >= proposal.expires_at
+    {
+        return Err(Error::ProposalClosed);
+    }
+    Ok(proposal)
+}
+
+/// Approvals from addresses still on the council. Signers removed by a
+/// council change no longer count towards the threshold.
+fn count_approvals(council: &AdminCouncil, proposal: &AdminProposal) -> u32 {
+    proposal
+        .approvals
+        .iter()
+        .filter(|a| council.signers.contains(a))
+        .count() as u32
+}
+
+/// Council changes and upgrades wait out the timelock; the rest can run as
+/// soon as they are approved.
+fn timelock_for(action: &AdminAction) -> u64 {
+    match action {
+        AdminAction::SetCouncil(..) | AdminAction::Upgrade(_) => COUNCIL_TIMELOCK,
+        _ => 0,
+    }
+}
+
+/// Carry out an admin action. Callers have already checked that `by` may.
+fn apply_admin_action(env: &Env, by: &Address, action: AdminAction) -> Result<(), Error> {
+    match action {
+        AdminAction::SetCouncil(signers, threshold) => {
+            let council = AdminCouncil { signers, threshold };
+            validate_council(&council)?;
+            storage::set_admin_council(env, &council);
+            env.events()
+                .publish((Symbol::new(env, "council_set"), by.clone()), council);
+        }
+        AdminAction::Upgrade(wasm_hash) => {
+            env.deployer()
+                .update_current_contract_wasm(wasm_hash.clone());
+            env.events()
+                .publish((Symbol::new(env, "upgraded"), by.clone()), wasm_hash);
+        }
+        AdminAction::Pause(scope, reason, duration) => {
+            validate_pause_reason(&reason)?;
+            record_pause(env, by, scope, reason, duration);
+        }
+        AdminAction::Unpause(scope) => {
+            if storage::get_pause(env, &scope).is_none() {
+                return Err(Error::PauseNotFound);
+            }
+            storage::remove_pause(env, &scope);
+            env.events()
+                .publish((Symbol::new(env, "unpaused"), by.clone()), scope);
+        }
+        AdminAction::SetGuardian(guardian) => {
+            storage::set_guardian(env, &guardian);
+            env.events()
+                .publish((Symbol::new(env, "guardian_set"),), guardian);
+        }
+        AdminAction::RemoveGuardian => {
+            storage::remove_guardian(env);
+            env.events()
+                .publish((Symbol::new(env, "guardian_removed"),), by.clone());
+        }
+        AdminAction::SetCertIssuer(issuer, name, cert_types) => {
+            const MAX_NAME_LEN: u32 = 128;
+            const MAX_CERT_TYPES: u32 = 20;
+
+            if !validation::non_empty(&name) || !validation::max_len(&name, MAX_NAME_LEN) {
+                return Err(Error::InvalidInput);
+            }
+            if cert_types.is_empty() || cert_types.len() > MAX_CERT_TYPES {
+                return Err(Error::InvalidInput);
+            }
+
+            let record = CertIssuer {
+                issuer: issuer.clone(),
+                name,
+                cert_types,
+                active: true,
+            };
+            storage::put_cert_issuer(env, &record);
+
+            env.events()
+                .publish((Symbol::new(env, "cert_issuer_set"), issuer), record);
+        }
+        AdminAction::RemoveCertIssuer(issuer) => {
+            let mut record =
+                storage::get_cert_issuer(env, &issuer).ok_or(Error::IssuerNotRegistered)?;
+            record.active = false;
+            storage::put_cert_issuer(env, &record);
+
+            env.events().publish(
+                (Symbol::new(env, "cert_issuer_removed"), issuer),
+                by.clone(),
+            );
+        }
+        AdminAction::SetCategoryLifecycle(category, lifecycle) => {
+            const MAX_TRANSITIONS: u32 = 100;
+
+            if lifecycle.initial.is_empty() {
+                return Err(Error::InvalidInput);
+            }
+            if lifecycle.transitions.len() > MAX_TRANSITIONS {
+                return Err(Error::InvalidInput);
+            }
+
+            storage::put_category_lifecycle(env, &category, &lifecycle);
+
+            env.events()
+                .publish((Symbol::new(env, "lifecycle_set"), category), lifecycle);
+        }
+        AdminAction::RemoveCategoryLifecycle(category) => {
+            storage::remove_category_lifecycle(env, &category);
+
+            env.events().publish(
+                (Symbol::new(env, "lifecycle_removed"), category),
+                by.clone(),
+            );
+        }
+        AdminAction::InitiateRecall(scope, reason, severity) => {
+            start_recall(env, by, true, scope, reason, severity)?;
+        }
+    }
+    Ok(())
+}
+
+// ─── Contract ────────────────────────────────────────────────────────────────
+
+#[contract]
+pub struct ChainLogisticsContract;
+
+#[contractimpl]
+impl ChainLogisticsContract {
+    pub fn init(env: Env, admin: Address) -> Result<(), Error> {
+        if storage::has_admin(&env) {
+            return Err(Error::AlreadyInitialized);
+        }
+        admin.require_auth();
+        storage::set_admin(&env, &admin);
+        Ok(())
+    }
+
+    pub fn get_admin(env: Env) -> Result<Address, Error> {
+        storage::get_admin(&env).ok_or(Error::NotInitialized)
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        is_scope_paused(&env, &PauseScope::All)
+    }
+
+    pub fn pause(env: Env, admin: Address) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        let reason = String::from_str(&env, "paused by admin");
+        record_pause(&env, &admin, PauseScope::All, reason, 0);
+        Ok(())
+    }
+
+    pub fn unpause(env: Env, admin: Address) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        storage::remove_pause(&env, &PauseScope::All);
+        Ok(())
+    }
+
+    /// Offer admin rights to `new_admin`. The handover can be accepted with
+    /// `accept_admin` once `COUNCIL_TIMELOCK` has passed, and for
+    /// `PROPOSAL_LIFETIME` after that. A new offer replaces any existing one.
+    /// Admin only; a council changes itself through a `SetCouncil` proposal.
+    pub fn transfer_admin(
+        env: Env,
+        admin: Address,
+        new_admin: Address,
+    ) -> Result<AdminHandover, Error> {
+        require_admin(&env, &admin)?;
+        if new_admin == admin {
+            return Err(Error::InvalidInput);
+        }
+
+        let now = env.ledger().timestamp();
+        let ready_at = now.saturating_add(COUNCIL_TIMELOCK);
+        let handover = AdminHandover {
+            from: admin.clone(),
+            to: new_admin,
+            created_at: now,
+            ready_at,
+            expires_at: ready_at.saturating_add(PROPOSAL_LIFETIME),
+        };
+        storage::put_admin_handover(&env, &handover);
+
+        env.events().publish(
+            (Symbol::new(&env, "admin_transfer_proposed"), admin),
+            handover.clone(),
+        );
+
+        Ok(handover)
+    }
+
+    /// Take over admin rights offered by `transfer_admin`. Must be signed by
+    /// the offered admin, after the timelock and before the offer expires.
+    pub fn accept_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        if storage::get_admin_council(&env).is_some() {
+            return Err(Error::CouncilActive);
+        }
+        let handover = storage::get_admin_handover(&env).ok_or(Error::ProposalNotFound)?;
+
+        new_admin.require_auth();
+        if handover.to != new_admin {
+            return Err(Error::Unauthorized);
+        }
+
+        let now = env.ledger().timestamp();
+        if now < handover.ready_at {
+            return Err(Error::ProposalNotReady);
+        }
+        if now >= handover.expires_at {
+            return Err(Error::ProposalClosed);
+        }
+
+        storage::set_admin(&env, &new_admin);
+        storage::remove_admin_handover(&env);
+
+        env.events().publish(
+            (Symbol::new(&env, "admin_transferred"), handover.from),
+            new_admin,
+        );
+
+        Ok(())
+    }
+
+    /// Withdraw a pending admin handover. Admin only.
+    pub fn cancel_admin_transfer(env: Env, admin: Address) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        let handover = storage::get_admin_handover(&env).ok_or(Error::ProposalNotFound)?;
+        storage::remove_admin_handover(&env);
+
+        env.events().publish(
+            (Symbol::new(&env, "admin_transfer_cancelled"), admin),
+            handover.to,
+        );
+
+        Ok(())
+    }
+
+    /// The pending admin handover, if any.
+    pub fn get_admin_handover(env: Env) -> Result<AdminHandover, Error> {
+        storage::get_admin_handover(&env).ok_or(Error::ProposalNotFound)
+    }
+
+    // --- Lifecycle Management ---
+    // ═══════════════════════════════════════════════════════════════════════
+    // PRODUCT REGISTRATION
+    // ═══════════════════════════════════════════════════════════════════════
+
+    /// Register a new product.
+    pub fn register_product(
+        env: Env,
+        owner: Address,
+        config: ProductConfig,
+    ) -> Result<Product, Error> {
+        owner.require_auth();
+        create_product(&env, &owner, config)
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════
+    // PRODUCT LIFECYCLE — DEACTIVATION & REACTIVATION
+    // ═══════════════════════════════════════════════════════════════════════
+
+    /// Deactivate a product.
+    pub fn deactivate_product(
+        env: Env,
+        owner: Address,
+        product_id: String,
+        reason: String,
+    ) -> Result<(), Error> {
+        let product = read_product(&env, &product_id)?;
+        require_owner(&product, &owner)?;
+        require_product_unpaused(&env, PauseScope::All, &product)?;
+
+        if !product.active {
+            return Err(Error::ProductDeactivated);
+        }
+
+        if !validation::non_empty(&reason) {
+            return Err(Error::DeactivationReasonRequired);
+        }
+
+        mark_deactivated(&env, product, &owner, reason);
+        Ok(())
     }
 
     /// Reactivate a product.
-    pub fn reactivate_product(
+    pub fn reactivate_product(env: Env, owner: Address, product_id: String) -> Result<(), Error> {
+        let mut product = read_product(&env, &product_id)?;
+        require_owner(&product, &owner)?;
+        require_product_unpaused(&env, PauseScope::All, &product)?;
+
+        if product.active {
+            return Err(Error::ProductAlreadyActive);
+        }
+
+        product.active = true;
+        product.deactivation_info = Vec::new(&env);
+
+        write_product(&env, &product);
+
+        // Increment active counter
+        let active = storage::get_active_products(&env) + 1;
+        storage::set_active_products(&env, active);
+
+        env.events().publish(
+            (Symbol::new(&env, "product_reactivated"), product_id.clone()),
+            owner,
+        );
+
+        Ok(())
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════
+    // PRODUCT METADATA
+    // ═══════════════════════════════════════════════════════════════════════
+
+    /// Replace a product's name, description, tags, media hashes and custom
+    /// fields. The ID, origin, category and creation time cannot change.
+    ///
+    /// Each update appends a version recording the editor, time, the fields
+    /// that changed and the hash of the metadata it replaced.
+    pub fn update_product_metadata(
+        env: Env,
+        owner: Address,
+        product_id: String,
+        metadata: ProductMetadata,
+    ) -> Result<ProductVersion, Error> {
+        let mut product = read_product(&env, &product_id)?;
+        require_owner(&product, &owner)?;
+        require_product_unpaused(&env, PauseScope::All, &product)?;
+
+        if !product.active {
+            return Err(Error::ProductDeactivated);
+        }
+        validate_metadata(&metadata)?;
+
+        let previous = product_metadata(&product);
+        if previous == metadata {
+            return Err(Error::InvalidInput);
+        }
+
+        for i in 0..previous.tags.len() {
+            let tag = previous.tags.get_unchecked(i);
+            if !metadata.tags.contains(&tag) {
+                storage::remove_from_index(&env, &ProductIndex::Tag(tag), &product_id);
+            }
+        }
+        for i in 0..metadata.tags.len() {
+            let tag = ProductIndex::Tag(metadata.tags.get_unchecked(i));
+            storage::add_to_index(&env, &tag, &product_id);
+        }
+
+        product.name = metadata.name;
+        product.description = metadata.description;
+        product.tags = metadata.tags;
+        product.media_hashes = metadata.media_hashes;
+        product.custom = metadata.custom;
+        write_product(&env, &product);
+
+        let version = record_version(&env, &product, &owner, Some(&previous));
+
+        env.events().publish(
+            (Symbol::new(&env, "product_updated"), product_id),
+            (version.version, owner, version.changed.clone()),
+        );
+
+        Ok(version)
+    }
+
+    /// Get one entry of a product's metadata history. Version 1 is the
+    /// metadata the product was registered with.
+    pub fn get_product_version(
+        env: Env,
+        product_id: String,
+        version: u32,
+    ) -> Result<ProductVersion, Error> {
+        let _ = read_product(&env, &product_id)?;
+        storage::get_product_version(&env, &product_id, version).ok_or(Error::VersionNotFound)
+    }
+
+    /// Number of entries in a product's metadata history.
+    pub fn get_product_version_count(env: Env, product_id: String) -> Result<u32, Error> {
+        let _ = read_product(&env, &product_id)?;
+        Ok(storage::get_product_version_count(&env, &product_id))
+    }
+}
+
+#[contractimpl]
+impl ChainLogisticsContract {
+    // --- Product Queries ---
+
+    /// Get a product by its string ID.
+    pub fn get_product(env: Env, id: String) -> Result<Product, Error> {
+        read_product(&env, &id)
+    }
+
+    /// Returns all event IDs associated with a product.
+    pub fn get_product_event_ids(env: Env, id: String) -> Result<Vec<u64>, Error> {
+        let _ = read_product(&env, &id)?;
+        Ok(storage::get_product_event_ids(&env, &id))
+    }
+
+    /// Get global product statistics.
+    pub fn get_stats(env: Env) -> ProductStats {
+        ProductStats {
+            total_products: storage::get_total_products(&env),
+            active_products: storage::get_active_products(&env),
+        }
+    }
+
+    /// List the products `owner` currently holds, in the order they were
+    /// acquired. Deactivated products stay listed with `active` false.
+    pub fn get_products_by_owner(env: Env, owner: Address, offset: u64, limit: u64) -> ProductPage {
+        product_page(&env, &ProductIndex::Owner(owner), offset, limit)
+    }
+
+    /// List products registered under `category`, oldest first.
+    pub fn get_products_by_category(
+        env: Env,
+        category: String,
+        offset: u64,
+        limit: u64,
+    ) -> ProductPage {
+        product_page(&env, &ProductIndex::Category(category), offset, limit)
+    }
+
+    /// List products carrying `tag`, oldest first.
+    pub fn get_products_by_tag(env: Env, tag: String, offset: u64, limit: u64) -> ProductPage {
+        product_page(&env, &ProductIndex::Tag(tag), offset, limit)
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════
+    // AUTHORIZATION MANAGEMENT
+    // ═══════════════════════════════════════════════════════════════════════
+
+    /// Grant an actor a role on a product. The role decides which event
+    /// types the actor may add; granting again replaces the previous role.
+    pub fn add_authorized_actor(
+        env: Env,
+        owner: Address,
+        product_id: String,
+        actor: Address,
+        role: ActorRole,
+    ) -> Result<(), Error> {
+        let product = read_product(&env, &product_id)?;
+        require_owner(&product, &owner)?;
+        require_product_unpaused(&env, PauseScope::All, &product)?;
+        storage::put_actor_grant(
+            &env,
+            &product_id,
+            &ActorGrant {
+                actor,
+                role,
+                valid_from: 0,
+                valid_until: u64::MAX,
+            },
+        );
+        Ok(())
+    }
+
+    /// Grant an actor a role that only applies between `valid_from` and
+    /// `valid_until` (inclusive ledger timestamps). Outside that window the
+    /// actor is treated as if `remove_authorized_actor` had been called.
+    pub fn add_temporary_actor(
+        env: Env,
+        owner: Address,
+        product_id: String,
+        actor: Address,
+        role: ActorRole,
+        valid_from: u64,
+        valid_until: u64,
+    ) -> Result<(), Error> {
+        let product = read_product(&env, &product_id)?;
+        require_owner(&product, &owner)?;
+        require_product_unpaused(&env, PauseScope::All, &product)?;
+
+        if valid_from > valid_until || valid_until < env.ledger().timestamp() {
+            return Err(Error::InvalidGrantWindow);
+        }
+
+        storage::put_actor_grant(
+            &env,
+            &product_id,
+            &ActorGrant {
+                actor,
+                role,
+                valid_from,
+                valid_until,
+            },
+        );
+        Ok(())
+    }
+
+    /// Revoke an actor's authorization to add events to a product.
+    pub fn remove_authorized_actor(
+        env: Env,
+        owner: Address,
+        product_id: String,
+        actor: Address,
+    ) -> Result<(), Error> {
+        let product = read_product(&env, &product_id)?;
+        require_owner(&product, &owner)?;
+        require_product_unpaused(&env, PauseScope::All, &product)?;
+        storage::remove_actor_grant(&env, &product_id, &actor);
+        Ok(())
+    }
+
+    /// Check whether an actor is authorized to add events to a product.
+    pub fn is_authorized(env: Env, product_id: String, actor: Address) -> Result<bool, Error> {
+        let product = read_product(&env, &product_id)?;
+        if product.owner == actor {
+            return Ok(true);
+        }
+        Ok(effective_grant(&env, &product_id, &actor).is_some())
+    }
+
+    /// List the actors whose grants are in effect right now, with their windows.
+    pub fn get_effective_actors(env: Env, product_id: String) -> Result<Vec<ActorGrant>, Error> {
+        let _ = read_product(&env, &product_id)?;
+        Ok(effective_grants(&env, &product_id))
+    }
+
+    /// List authorized actors (including the owner) with pagination.
+    pub fn get_authorized_actors(
+        env: Env,
+        product_id: String,
+        offset: u64,
+        limit: u64,
+    ) -> Result<ActorPage, Error> {
+        let _ = read_product(&env, &product_id)?;
+
+        let grants = effective_grants(&env, &product_id);
+        let total_count = grants.len() as u64;
+
+        let mut actors = Vec::new(&env);
+        if offset < total_count {
+            let start = offset as u32;
+            let end = (offset.saturating_add(limit)).min(total_count) as u32;
+            for i in start..end {
+                actors.push_back(grants.get_unchecked(i));
+            }
+        }
+
+        let has_more = offset + (actors.len() as u64) < total_count;
+
+        Ok(ActorPage {
+            actors,
+            total_count,
+            has_more,
+        })
+    }
+
+    /// Get the role an actor holds on a product. The owner is always `Manager`.
+    pub fn get_actor_role(
+        env: Env,
+        product_id: String,
+        actor: Address,
+    ) -> Result<ActorRole, Error> {
+        let product = read_product(&env, &product_id)?;
+        if product.owner == actor {
+            return Ok(ActorRole::Manager);
+        }
+        effective_grant(&env, &product_id, &actor)
+            .map(|grant| grant.role)
+            .ok_or(Error::ActorNotFound)
+    }
+
+    /// Replace the event types a role may log on a product.
+    pub fn set_role_permissions(
+        env: Env,
+        owner: Address,
+        product_id: String,
+        role: ActorRole,
+        event_types: Vec<Symbol>,
+    ) -> Result<(), Error> {
+        let product = read_product(&env, &product_id)?;
+        require_owner(&product, &owner)?;
+        require_product_unpaused(&env, PauseScope::All, &product)?;
+
+        // Managers are unrestricted; an allow-list for them would be ignored
+        if role == ActorRole::Manager {
+            return Err(Error::InvalidInput);
+        }
+
+        storage::put_role_permissions(&env, &product_id, &role, &event_types);
+        Ok(())
+    }
+
+    /// Get the event types a role may log on a product.
+    pub fn get_role_permissions(
+        env: Env,
+        product_id: String,
+        role: ActorRole,
+    ) -> Result<Vec<Symbol>, Error> {
+        let _ = read_product(&env, &product_id)?;
+        Ok(role_permissions(&env, &product_id, &role))
+    }
+}
+
+#[contractimpl]
+impl ChainLogisticsContract {
+    // --- Authorization & Transfer ---
+
+    /// Transfer product ownership to a new address.
+    pub fn transfer_product(
+        env: Env,
+        owner: Address,
+        product_id: String,
+        new_owner: Address,
+    ) -> Result<(), Error> {
+        let mut product = read_product(&env, &product_id)?;
+        require_owner(&product, &owner)?;
+        require_product_unpaused(&env, PauseScope::Transfers, &product)?;
+
+        new_owner.require_auth();
+        change_owner(&env, &mut product, &new_owner);
+
+        env.events().publish(
+            (Symbol::new(&env, "product_transferred"), product_id),
+            (owner, new_owner),
+        );
+
+        Ok(())
+    }
+
+    /// Offer ownership of a product to `new_owner`.
+    ///
+    /// The recipient completes the handover with `accept_transfer` before
+    /// `expires_at` (ledger timestamp). A new offer replaces any existing one.
+    pub fn propose_transfer(
+        env: Env,
+        owner: Address,
+        product_id: String,
+        new_owner: Address,
+        expires_at: u64,
+    ) -> Result<PendingTransfer, Error> {
+        let product = read_product(&env, &product_id)?;
+        require_owner(&product, &owner)?;
+        require_product_unpaused(&env, PauseScope::Transfers, &product)?;
+
+        if new_owner == owner {
+            return Err(Error::InvalidInput);
+        }
+
+        let now = env.ledger().timestamp();
+        if expires_at <= now {
+            return Err(Error::InvalidTransferExpiry);
+        }
+
+        let transfer = PendingTransfer {
+            product_id: product_id.clone(),
+            from: owner,
+            to: new_owner,
+            created_at: now,
+            expires_at,
+        };
+        storage::put_pending_transfer(&env, &transfer);
+
+        env.events().publish(
+            (Symbol::new(&env, "transfer_proposed"), product_id),
+            transfer.clone(),
+        );
+
+        Ok(transfer)
+    }
+
+    /// Accept a pending ownership offer. Must be signed by the offered recipient.
+    pub fn accept_transfer(env: Env, new_owner: Address, product_id: String) -> Result<(), Error> {
+        let mut product = read_product(&env, &product_id)?;
+        require_product_unpaused(&env, PauseScope::Transfers, &product)?;
+        let transfer =
+            storage::get_pending_transfer(&env, &product_id).ok_or(Error::TransferNotFound)?;
+
+        new_owner.require_auth();
+        if transfer.to != new_owner {
+            return Err(Error::Unauthorized);
+        }
+
+        if env.ledger().timestamp() > transfer.expires_at {
+            return Err(Error::TransferExpired);
+        }
+
+        change_owner(&env, &mut product, &new_owner);
+
+        env.events().publish(
+            (Symbol::new(&env, "transfer_accepted"), product_id),
+            (transfer.from, new_owner),
+        );
+
+        Ok(())
+    }
+
+    /// Withdraw a pending ownership offer.
+    pub fn cancel_transfer(env: Env, owner: Address, product_id: String) -> Result<(), Error> {
+        let product = read_product(&env, &product_id)?;
+        require_owner(&product, &owner)?;
+
+        let transfer =
+            storage::get_pending_transfer(&env, &product_id).ok_or(Error::TransferNotFound)?;
+        storage::remove_pending_transfer(&env, &product_id);
+
+        env.events().publish(
+            (Symbol::new(&env, "transfer_cancelled"), product_id),
+            (owner, transfer.to),
+        );
+
+        Ok(())
+    }
+
+    /// Get the pending ownership offer for a product, if any.
+    pub fn get_pending_transfer(env: Env, product_id: String) -> Result<PendingTransfer, Error> {
+        let _ = read_product(&env, &product_id)?;
+        storage::get_pending_transfer(&env, &product_id).ok_or(Error::TransferNotFound)
+    }
+}
+
+#[contractimpl]
+impl ChainLogisticsContract {
+    // --- Tracking Events ---
+
+    /// Add a tracking event to a product.
+    pub fn add_tracking_event(
+        env: Env,
+        actor: Address,
+        product_id: String,
+        event_type: Symbol,
+        location: Location,
+        data_hash: BytesN<32>,
+        note: String,
+        metadata: Map<Symbol, String>,
+    ) -> Result<u64, Error> {
+        actor.require_auth();
+        let input = TrackingEventInput {
+            product_id,
+            event_type,
+            location,
+            data_hash,
+            note,
+            metadata,
+        };
+        record_event(&env, &actor, input)
+    }
+
+    /// Add up to 50 tracking events for one actor, in order. Each is checked
+    /// as by `add_tracking_event`; if any fails, none are recorded.
+    pub fn add_tracking_events_batch(
+        env: Env,
+        actor: Address,
+        inputs: Vec<TrackingEventInput>,
+    ) -> Result<Vec<u64>, Error> {
+        const MAX_BATCH: u32 = 50;
+
+        if inputs.is_empty() || inputs.len() > MAX_BATCH {
+            return Err(Error::InvalidInput);
+        }
+
+        actor.require_auth();
+        let mut event_ids = Vec::new(&env);
+        for input in inputs.iter() {
+            event_ids.push_back(record_event(&env, &actor, input)?);
+        }
+        Ok(event_ids)
+    }
+
+    /// Get a single tracking event by its numeric ID.
+    pub fn get_event(env: Env, event_id: u64) -> Result<TrackingEvent, Error> {
+        storage::get_event(&env, event_id).ok_or(Error::EventNotFound)
+    }
+
+    /// Get all events for a product, `offset` events in. The page's
+    /// `next_cursor` continues the listing through `get_next_events`.
+    pub fn get_product_events(
+        env: Env,
+        product_id: String,
+        offset: u64,
+        limit: u64,
+    ) -> Result<TrackingEventPage, Error> {
+        let _ = read_product(&env, &product_id)?;
+        Ok(first_event_page(
+            &env,
+            product_id,
+            EventQuery::All,
+            offset,
+            limit,
+        ))
+    }
+
+    /// Get events for a product filtered by event type, with pagination.
+    pub fn get_events_by_type(
+        env: Env,
+        product_id: String,
+        event_type: Symbol,
+        offset: u64,
+        limit: u64,
+    ) -> Result<TrackingEventPage, Error> {
+        let _ = read_product(&env, &product_id)?;
+        Ok(first_event_page(
+            &env,
+            product_id,
+            EventQuery::ByType(event_type),
+            offset,
+            limit,
+        ))
+    }
+
+    /// Get events filtered by time range with pagination.
+    pub fn get_events_by_time_range(
+        env: Env,
+        product_id: String,
+        start_time: u64,
+        end_time: u64,
+        offset: u64,
+        limit: u64,
+    ) -> Result<TrackingEventPage, Error> {
+        let _ = read_product(&env, &product_id)?;
+        Ok(first_event_page(
+            &env,
+            product_id,
+            EventQuery::TimeRange(start_time, end_time),
+            offset,
+            limit,
+        ))
+    }
+
+    /// Get events with composite filter (type + time range + location name,
+    /// country, facility or geohash prefix).
+    ///
+    /// Only the events needed for the page are read. When the matches do not
+    /// fit in one page, `total_count` is the number of events in the time
+    /// range and `total_is_estimate` is set.
+    pub fn get_filtered_events(
+        env: Env,
+        product_id: String,
+        filter: TrackingEventFilter,
+        offset: u64,
+        limit: u64,
+    ) -> Result<TrackingEventPage, Error> {
+        let _ = read_product(&env, &product_id)?;
+        Ok(first_event_page(
+            &env,
+            product_id,
+            EventQuery::Filtered(filter),
+            offset,
+            limit,
+        ))
+    }
+
+    /// Get the events `actor` recorded on one product, with pagination.
+    pub fn get_product_events_by_actor(
+        env: Env,
+        product_id: String,
+        actor: Address,
+        offset: u64,
+        limit: u64,
+    ) -> Result<TrackingEventPage, Error> {
+        let _ = read_product(&env, &product_id)?;
+        Ok(first_event_page(
+            &env,
+            product_id,
+            EventQuery::ByActor(actor),
+            offset,
+            limit,
+        ))
+    }
+
+    /// Get the events `actor` recorded across every product, oldest first,
+    /// e.g. to audit everything one carrier has touched.
+    pub fn get_events_by_actor(
+        env: Env,
+        actor: Address,
+        offset: u64,
+        limit: u64,
+    ) -> TrackingEventPage {
+        first_event_page(
+            &env,
+            String::from_str(&env, ""),
+            EventQuery::ActorAcrossProducts(actor),
+            offset,
+            limit,
+        )
+    }
+
+    /// Continue any of the event queries above from a page's `next_cursor`.
+    ///
+    /// The scan resumes after the last event returned rather than counting
+    /// from the start again. Events recorded after the first page was read
+    /// are left out, so pages neither repeat nor skip events and
+    /// `total_count` stays the same throughout.
+    pub fn get_next_events(
+        env: Env,
+        cursor: Bytes,
+        limit: u64,
+    ) -> Result<TrackingEventPage, Error> {
+        let cursor = read_event_cursor(&env, &cursor)?;
+        Ok(read_event_page(&env, cursor, 0, limit).0)
+    }
+
+    /// Recompute a product's event hash chain and report the first broken
+    /// link.
+    pub fn verify_event_chain(env: Env, product_id: String) -> Result<EventChainReport, Error> {
+        let _ = read_product(&env, &product_id)?;
+
+        let mut expected = BytesN::from_array(&env, &[0u8; 32]);
+        let ids = storage::get_product_event_ids(&env, &product_id);
+        let mut events_checked = 0u64;
+
+        for i in 0..ids.len() {
+            let eid = ids.get_unchecked(i);
+            let event = storage::get_event(&env, eid).ok_or(Error::EventNotFound)?;
+            events_checked += 1;
+
+            if event.prev_hash != expected {
+                return Ok(EventChainReport {
+                    valid: false,
+                    events_checked,
+                    broken_at: eid,
+                });
+            }
+            expected = event_hash(&env, &event);
+        }
+
+        Ok(EventChainReport {
+            valid: true,
+            events_checked,
+            broken_at: 0,
+        })
+    }
+
+    /// Merkle root over the product's event hashes. A single event can be
+    /// checked against it with an inclusion proof, without fetching the rest
+    /// of the history.
+    pub fn get_event_merkle_root(env: Env, product_id: String) -> Result<EventMerkleRoot, Error> {
+        let _ = read_product(&env, &product_id)?;
+        Ok(match storage::get_event_merkle(&env, &product_id) {
+            Some(tree) => EventMerkleRoot {
+                root: tree.root,
+                size: tree.size,
+            },
+            None => EventMerkleRoot {
+                root: merkle_empty_root(&env),
+                size: 0,
+            },
+        })
+    }
+
+    /// Merkle root as it was when the product had `size` events. Proofs
+    /// record the tree size they were built for, so they can be checked here
+    /// after further events have been added.
+    pub fn get_event_merkle_root_at(
+        env: Env,
+        product_id: String,
+        size: u64,
+    ) -> Result<BytesN<32>, Error> {
+        let _ = read_product(&env, &product_id)?;
+        if size == 0 {
+            return Ok(merkle_empty_root(&env));
+        }
+        storage::get_event_merkle_root_at(&env, &product_id, size).ok_or(Error::MerkleRootNotFound)
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════
+    // STORAGE TTL
+    // ═══════════════════════════════════════════════════════════════════════
+
+    /// Keep a product's records live for at least `ledgers` more ledgers.
+    ///
+    /// A product's history can outgrow what one call may touch, so its lists
+    /// are extended a page at a time. Each call extends the product's single
+    /// records and positions `offset..offset + limit` of its events, actors,
+    /// metadata versions, attestations and children. An event is extended
+    /// with its ID and timestamp page, the Merkle root at its size, its
+    /// device attribution and telemetry, and its entries in the event-type
+    /// and actor indexes; an actor with its grant, an attestation with its
+    /// record. Returns whether any list runs past this page, in which case
+    /// call again from `offset + limit` until the whole history is covered.
+    pub fn extend_product_ttl(
+        env: Env,
+        owner: Address,
+        product_id: String,
+        ledgers: u32,
+        offset: u64,
+        limit: u64,
+    ) -> Result<bool, Error> {
+        let product = read_product(&env, &product_id)?;
+        require_owner(&product, &owner)?;
+        if ledgers == 0 || ledgers > env.storage().max_ttl() {
+            return Err(Error::InvalidInput);
+        }
+        storage::extend_product_entries(&env, &product_id, ledgers);
+
+        let end = offset.saturating_add(limit);
+        let window = |len: u64| offset.min(len)..end.min(len);
+
+        let event_count = storage::get_product_event_count(&env, &product_id);
+        let events = window(event_count);
+        storage::extend_product_event_pages(&env, &product_id, events.start, events.end, ledgers);
+        let ids = storage::get_product_event_ids_paginated(&env, &product_id, offset, limit);
+        for (i, eid) in ids.iter().enumerate() {
+            storage::extend_event(&env, eid, ledgers);
+            let size = events.start + i as u64 + 1;
+            storage::extend_event_merkle_root_at(&env, &product_id, size, ledgers);
+            if let Some(event) = storage::get_event(&env, eid) {
+                storage::extend_event_index_entries(&env, &event, ledgers);
+            }
+        }
+
+        let actors = storage::get_product_actors(&env, &product_id);
+        for i in window(actors.len() as u64) {
+            let actor = actors.get_unchecked(i as u32);
+            storage::extend_actor_grant(&env, &product_id, &actor, ledgers);
+        }
+
+        let version_count = storage::get_product_version_count(&env, &product_id) as u64;
+        for i in window(version_count) {
+            storage::extend_product_version(&env, &product_id, i as u32 + 1, ledgers);
+        }
+
+        let attestation_count = storage::get_product_attestation_count(&env, &product_id);
+        for i in window(attestation_count) {
+            storage::extend_product_attestation(&env, &product_id, i + 1, ledgers);
+        }
+
+        let children = ProductIndex::Children(product_id.clone());
+        let child_count = storage::get_index_count(&env, &children);
+        for i in window(child_count) {
+            storage::extend_index_entry(&env, &children, i + 1, ledgers);
+        }
+
+        let longest = event_count
+            .max(actors.len() as u64)
+            .max(version_count)
+            .max(attestation_count)
+            .max(child_count);
+        Ok(end < longest)
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════
+    // EVENT COUNT HELPERS
+    // ═══════════════════════════════════════════════════════════════════════
+
+    /// Get total event count for a product.
+    pub fn get_event_count(env: Env, product_id: String) -> Result<u64, Error> {
+        let _ = read_product(&env, &product_id)?;
+        Ok(storage::get_product_event_count(&env, &product_id))
+    }
+
+    /// Get event count for a specific event type on a product.
+    pub fn get_event_count_by_type(
+        env: Env,
+        product_id: String,
+        event_type: Symbol,
+    ) -> Result<u64, Error> {
+        let _ = read_product(&env, &product_id)?;
+        Ok(storage::get_event_count_by_type(
+            &env,
+            &product_id,
+            &event_type,
+        ))
+    }
+}
+
+#[contractimpl]
+impl ChainLogisticsContract {
+    // --- Lineage ---
+
+    /// Split one product into several children, e.g. a pallet into cases.
+    /// The parent is deactivated once its contents are split out.
+    pub fn split_product(
+        env: Env,
+        owner: Address,
+        parent_id: String,
+        children: Vec<ProductConfig>,
+    ) -> Result<Vec<Product>, Error> {
+        const MAX_SPLIT: u32 = 50;
+
+        if children.is_empty() {
+            return Err(Error::InvalidInput);
+        }
+        if children.len() > MAX_SPLIT {
+            return Err(Error::InvalidInput);
+        }
+
+        let mut parent_ids = Vec::new(&env);
+        parent_ids.push_back(parent_id.clone());
+        read_lineage_parents(&env, &owner, &parent_ids)?;
+
+        let mut created = Vec::new(&env);
+        for i in 0..children.len() {
+            let child = create_product(&env, &owner, children.get_unchecked(i))?;
+            created.push_back(child);
+        }
+
+        record_lineage(&env, &owner, LineageOp::Split, &parent_ids, &created)?;
+
+        env.events().publish(
+            (Symbol::new(&env, "product_split"), parent_id),
+            created.clone(),
+        );
+
+        Ok(created)
+    }
+
+    /// Merge several lots into one new product. Every parent is deactivated.
+    pub fn merge_products(
+        env: Env,
+        owner: Address,
+        parent_ids: Vec<String>,
+        child: ProductConfig,
+    ) -> Result<Product, Error> {
+        const MAX_MERGE: u32 = 50;
+
+        if parent_ids.len() < 2 {
+            return Err(Error::InvalidInput);
+        }
+        if parent_ids.len() > MAX_MERGE {
+            return Err(Error::InvalidInput);
+        }
+        for i in 0..parent_ids.len() {
+            if parent_ids.first_index_of(parent_ids.get_unchecked(i)) != Some(i) {
+                return Err(Error::InvalidInput);
+            }
+        }
+
+        read_lineage_parents(&env, &owner, &parent_ids)?;
+
+        let product = create_product(&env, &owner, child)?;
+        let mut created = Vec::new(&env);
+        created.push_back(product.clone());
+
+        record_lineage(&env, &owner, LineageOp::Merge, &parent_ids, &created)?;
+
+        env.events().publish(
+            (Symbol::new(&env, "products_merged"), product.id.clone()),
+            parent_ids,
+        );
+
+        Ok(product)
+    }
+
+    /// Transform a product into a new one, e.g. green coffee into roasted.
+    /// The input product is deactivated.
+    pub fn transform_product(
+        env: Env,
+        owner: Address,
+        parent_id: String,
+        child: ProductConfig,
+    ) -> Result<Product, Error> {
+        let mut parent_ids = Vec::new(&env);
+        parent_ids.push_back(parent_id.clone());
+        read_lineage_parents(&env, &owner, &parent_ids)?;
+
+        let product = create_product(&env, &owner, child)?;
+        let mut created = Vec::new(&env);
+        created.push_back(product.clone());
+
+        record_lineage(&env, &owner, LineageOp::Transform, &parent_ids, &created)?;
+
+        env.events().publish(
+            (Symbol::new(&env, "product_transformed"), parent_id),
+            product.id.clone(),
+        );
+
+        Ok(product)
+    }
+
+    /// Walk ancestors and descendants of a product up to `depth` hops.
+    pub fn get_lineage(env: Env, product_id: String, depth: u32) -> Result<Lineage, Error> {
+        const MAX_DEPTH: u32 = 10;
+
+        let _ = read_product(&env, &product_id)?;
+        let depth = depth.min(MAX_DEPTH);
+
+        Ok(Lineage {
+            ancestors: walk_lineage(&env, &product_id, depth, true),
+            descendants: walk_lineage(&env, &product_id, depth, false),
+            product_id,
+        })
+    }
+}
+
+#[contractimpl]
+impl ChainLogisticsContract {
+    // --- Recalls ---
+
+    /// Start a recall campaign.
+    ///
+    /// An explicit product list may be recalled by the admin or by the owner
+    /// of every listed product; category and origin recalls are admin-only,
+    /// and a council runs them through an `InitiateRecall` proposal.
+    /// Lineage descendants and siblings of the matched products are flagged
+    /// too, after which only return and disposal events are accepted.
+    pub fn initiate_recall(
+        env: Env,
+        initiator: Address,
+        scope: RecallScope,
+        reason: String,
+        severity: RecallSeverity,
+    ) -> Result<Recall, Error> {
+        initiator.require_auth();
+        let is_admin = is_sole_admin(&env, &initiator);
+        start_recall(&env, &initiator, is_admin, scope, reason, severity)
+    }
+
+    /// Get a recall campaign by ID.
+    pub fn get_recall(env: Env, recall_id: u64) -> Result<Recall, Error> {
+        storage::get_recall(&env, recall_id).ok_or(Error::RecallNotFound)
+    }
+
+    /// Recall banner data for a product: the latest recall that flagged it.
+    pub fn get_recall_status(env: Env, product_id: String) -> Result<RecallStatus, Error> {
+        let _ = read_product(&env, &product_id)?;
+
+        let mut recall = Vec::new(&env);
+        if let Some(recall_id) = storage::get_product_recall(&env, &product_id) {
+            if let Some(r) = storage::get_recall(&env, recall_id) {
+                recall.push_back(r);
+            }
+        }
+
+        Ok(RecallStatus {
+            recalled: !recall.is_empty(),
+            recall,
+        })
+    }
+}
+
+#[contractimpl]
+impl ChainLogisticsContract {
+    // --- Custody Lifecycle ---
+
+    /// Define the allowed custody states and transitions for a category.
+    pub fn set_category_lifecycle(
+        env: Env,
+        admin: Address,
+        category: String,
+        lifecycle: Lifecycle,
+    ) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        apply_admin_action(
+            &env,
+            &admin,
+            AdminAction::SetCategoryLifecycle(category, lifecycle),
+        )
+    }
+
+    /// Stop enforcing a lifecycle for a category.
+    pub fn remove_category_lifecycle(
+        env: Env,
+        admin: Address,
+        category: String,
+    ) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        apply_admin_action(&env, &admin, AdminAction::RemoveCategoryLifecycle(category))
+    }
+
+    /// Get the lifecycle enforced for a category.
+    pub fn get_category_lifecycle(env: Env, category: String) -> Result<Lifecycle, Error> {
+        storage::get_category_lifecycle(&env, &category).ok_or(Error::InvalidCategory)
+    }
+
+    /// Current custody state of a product, or an empty symbol if it has not
+    /// entered a lifecycle state yet.
+    pub fn get_current_state(env: Env, product_id: String) -> Result<Symbol, Error> {
+        let _ = read_product(&env, &product_id)?;
+        Ok(storage::get_product_state(&env, &product_id).unwrap_or(Symbol::new(&env, "")))
+    }
+}
+
+#[contractimpl]
+impl ChainLogisticsContract {
+    // --- Telemetry ---
+
+    /// Set the ranges outside which telemetry readings count as excursions.
+    pub fn set_telemetry_thresholds(
         env: Env,
         owner: Address,
         product_id: String,
+        thresholds: TelemetryThresholds,
     ) -> Result<(), Error> {
-        let mut product = read_product(&env, &product_id)?;
+        let product = read_product(&env, &product_id)?;
         require_owner(&product, &owner)?;
+        require_product_unpaused(&env, PauseScope::All, &product)?;
 
-        if product.active {
-            return Err(Error::ProductAlreadyActive);
+        if thresholds.min_temperature > thresholds.max_temperature {
+            return Err(Error::InvalidInput);
         }
 
-        product.active = true;
-        product.deactivation_info = Vec::new(&env);
+        storage::put_telemetry_thresholds(&env, &product_id, &thresholds);
+        Ok(())
+    }
 
-        write_product(&env, &product);
+    pub fn get_telemetry_thresholds(
+        env: Env,
+        product_id: String,
+    ) -> Result<TelemetryThresholds, Error> {
+        let _ = read_product(&env, &product_id)?;
+        storage::get_telemetry_thresholds(&env, &product_id).ok_or(Error::ThresholdsNotSet)
+    }
 
-        // Increment active counter
-        let active = storage::get_active_products(&env) + 1;
-        storage::set_active_products(&env, active);
+    /// Submit a reading signed by a registered device.
+    ///
+    /// `signature` must be the device's Ed25519 signature over the XDR of
+    /// `(network_id, contract, (product_id, reading))`. Readings must be strictly newer than the
+    /// device's previous one, which stops replays. An out-of-range reading
+    /// additionally logs an `excursion` event. Returns the telemetry event ID.
+    pub fn submit_telemetry(
+        env: Env,
+        public_key: BytesN<32>,
+        product_id: String,
+        reading: TelemetryReading,
+        signature: BytesN<64>,
+    ) -> Result<u64, Error> {
+        let product = read_product(&env, &product_id)?;
+        require_product_unpaused(&env, PauseScope::EventWrites, &product)?;
+        let device = read_device_in_scope(&env, &public_key, &product_id)?;
+
+        let event_type = Symbol::new(&env, "telemetry");
+        check_can_add_event(&env, &product_id, &product, &device.sponsor, &event_type)?;
+
+        let payload = device_payload(&env, (product_id.clone(), reading.clone()));
+        verify_device_payload(&env, &public_key, &payload, &signature, reading.recorded_at)?;
+
+        let mut location = unknown_location(&env);
+        location.coordinates.push_back(Coordinates {
+            latitude: reading.latitude,
+            longitude: reading.longitude,
+        });
+        validate_location(&location)?;
+
+        let reading_hash = env.crypto().sha256(&reading.clone().to_xdr(&env));
+        let event = append_event(
+            &env,
+            &product_id,
+            device.sponsor.clone(),
+            location.clone(),
+            event_type,
+            reading_hash.clone(),
+            String::from_str(&env, ""),
+            Map::new(&env),
+        );
+        storage::put_telemetry(&env, event.event_id, &reading);
+        storage::put_event_device(&env, event.event_id, &public_key);
+
+        if let Some(thresholds) = storage::get_telemetry_thresholds(&env, &product_id) {
+            if is_excursion(&reading, &thresholds) {
+                let excursion = append_event(
+                    &env,
+                    &product_id,
+                    device.sponsor,
+                    location,
+                    Symbol::new(&env, "excursion"),
+                    reading_hash,
+                    String::from_str(&env, "Telemetry reading outside thresholds"),
+                    Map::new(&env),
+                );
+                storage::put_event_device(&env, excursion.event_id, &public_key);
+                env.events().publish(
+                    (
+                        Symbol::new(&env, "excursion"),
+                        product_id,
+                        excursion.event_id,
+                    ),
+                    reading,
+                );
+            }
+        }
+
+        Ok(event.event_id)
+    }
+
+    /// Get the typed reading stored with a telemetry event.
+    pub fn get_telemetry(env: Env, event_id: u64) -> Result<TelemetryReading, Error> {
+        storage::get_telemetry(&env, event_id).ok_or(Error::EventNotFound)
+    }
+}
+
+#[contractimpl]
+impl ChainLogisticsContract {
+    // --- Device Registry ---
+
+    /// Register a device key that may report for the products in `scope`.
+    /// The sponsor must be the owner or an authorized actor of each product.
+    pub fn register_device(
+        env: Env,
+        sponsor: Address,
+        public_key: BytesN<32>,
+        label: String,
+        scope: Vec<String>,
+    ) -> Result<Device, Error> {
+        const MAX_LABEL_LEN: u32 = 64;
+        const MAX_SCOPE: u32 = 50;
+
+        sponsor.require_auth();
+
+        if !validation::non_empty(&label) || !validation::max_len(&label, MAX_LABEL_LEN) {
+            return Err(Error::InvalidInput);
+        }
+        if scope.is_empty() {
+            return Err(Error::InvalidInput);
+        }
+        if scope.len() > MAX_SCOPE {
+            return Err(Error::InvalidInput);
+        }
+        if storage::get_device(&env, &public_key).is_some() {
+            return Err(Error::DeviceAlreadyRegistered);
+        }
+
+        for i in 0..scope.len() {
+            let product_id = scope.get_unchecked(i);
+            let product = read_product(&env, &product_id)?;
+            if product.owner != sponsor && effective_grant(&env, &product_id, &sponsor).is_none() {
+                return Err(Error::Unauthorized);
+            }
+        }
+
+        let device = Device {
+            public_key: public_key.clone(),
+            label,
+            sponsor,
+            scope,
+            active: true,
+            registered_at: env.ledger().timestamp(),
+            rotated_to: Vec::new(&env),
+        };
+        storage::put_device(&env, &device);
 
         env.events().publish(
-            (Symbol::new(&env, "product_reactivated"), product_id.clone()),
-            owner,
+            (Symbol::new(&env, "device_registered"), public_key),
+            device.clone(),
         );
 
-        Ok(())
+        Ok(device)
     }
-}
 
-#[contractimpl]
-impl ChainLogisticsContract {
-    // --- Product Queries ---
+    /// Replace a device's key, e.g. after re-provisioning the logger. The old
+    /// key stops being accepted immediately. Its record is kept, inactive and
+    /// pointing at the new key, so events it signed stay attributable.
+    pub fn rotate_device_key(
+        env: Env,
+        sponsor: Address,
+        old_key: BytesN<32>,
+        new_key: BytesN<32>,
+    ) -> Result<Device, Error> {
+        let mut device = storage::get_device(&env, &old_key).ok_or(Error::DeviceNotRegistered)?;
+        sponsor.require_auth();
+        if device.sponsor != sponsor {
+            return Err(Error::Unauthorized);
+        }
+        if !device.active {
+            return Err(Error::DeviceRevoked);
+        }
+        if storage::get_device(&env, &new_key).is_some() {
+            return Err(Error::DeviceAlreadyRegistered);
+        }
 
-    /// Get a product by its string ID.
-    pub fn get_product(env: Env, id: String) -> Result<Product, Error> {
-        read_product(&env, &id)
-    }
+        let mut rotated = device.clone();
+        rotated.public_key = new_key.clone();
+        rotated.registered_at = env.ledger().timestamp();
+        storage::put_device(&env, &rotated);
 
-    /// Returns all event IDs associated with a product.
-    pub fn get_product_event_ids(env: Env, id: String) -> Result<Vec<u64>, Error> {
-        let _ = read_product(&env, &id)?;
-        Ok(storage::get_product_event_ids(&env, &id))
+        device.active = false;
+        device.rotated_to = Vec::from_array(&env, [new_key.clone()]);
+        storage::put_device(&env, &device);
+
+        env.events()
+            .publish((Symbol::new(&env, "device_rotated"), old_key), new_key);
+
+        Ok(rotated)
     }
 
-    /// Get global product statistics.
-    pub fn get_stats(env: Env) -> ProductStats {
-        ProductStats {
-            total_products: storage::get_total_products(&env),
-            active_products: storage::get_active_products(&env),
+    /// Permanently stop accepting submissions from a device. The record is
+    /// kept so past events stay attributable.
+    pub fn revoke_device(env: Env, sponsor: Address, public_key: BytesN<32>) -> Result<(), Error> {
+        let mut device =
+            storage::get_device(&env, &public_key).ok_or(Error::DeviceNotRegistered)?;
+        sponsor.require_auth();
+        if device.sponsor != sponsor {
+            return Err(Error::Unauthorized);
         }
-    }
 
-<<<<<<< nnennaokoye/chainlogistics
-    pub fn add_authorized_actor(env: Env, owner: Address, product_id: String, actor: Address) -> Result<(), Error> {
-        require_not_paused(&env)?;
-=======
-    // ═══════════════════════════════════════════════════════════════════════
-    // AUTHORIZATION MANAGEMENT
-    // ═══════════════════════════════════════════════════════════════════════
+        device.active = false;
+        storage::put_device(&env, &device);
+
+        env.events()
+            .publish((Symbol::new(&env, "device_revoked"), public_key), sponsor);
 
-    /// Grant an actor the right to add tracking events to a product.
-    pub fn add_authorized_actor(
-        env: Env,
-        owner: Address,
-        product_id: String,
-        actor: Address,
-    ) -> Result<(), Error> {
->>>>>>> main
-        let product = read_product(&env, &product_id)?;
-        require_owner(&product, &owner)?;
-        storage::set_auth(&env, &product_id, &actor, true);
         Ok(())
     }
 
-<<<<<<< nnennaokoye/chainlogistics
-    pub fn remove_authorized_actor(env: Env, owner: Address, product_id: String, actor: Address) -> Result<(), Error> {
-        require_not_paused(&env)?;
-=======
-    /// Revoke an actor's authorization to add events to a product.
-    pub fn remove_authorized_actor(
+    pub fn get_device(env: Env, public_key: BytesN<32>) -> Result<Device, Error> {
+        storage::get_device(&env, &public_key).ok_or(Error::DeviceNotRegistered)
+    }
+
+    /// Add a tracking event signed by a registered device.
+    ///
+    /// `signature` must be the device's Ed25519 signature over the XDR of
+    /// `(network_id, contract, (product_id, event_type, location, data_hash,
+    /// note, recorded_at))`. The event is recorded with the device's sponsor
+    /// as actor, subject to the sponsor's role on the product.
+    pub fn add_device_event(
         env: Env,
-        owner: Address,
+        public_key: BytesN<32>,
         product_id: String,
-        actor: Address,
-    ) -> Result<(), Error> {
->>>>>>> main
+        event_type: Symbol,
+        location: Location,
+        data_hash: BytesN<32>,
+        note: String,
+        recorded_at: u64,
+        signature: BytesN<64>,
+    ) -> Result<u64, Error> {
         let product = read_product(&env, &product_id)?;
-        require_owner(&product, &owner)?;
-        storage::set_auth(&env, &product_id, &actor, false);
-        Ok(())
+        require_product_unpaused(&env, PauseScope::EventWrites, &product)?;
+        let device = read_device_in_scope(&env, &public_key, &product_id)?;
+        check_can_add_event(&env, &product_id, &product, &device.sponsor, &event_type)?;
+        validate_location(&location)?;
+
+        let payload = device_payload(
+            &env,
+            (
+                product_id.clone(),
+                event_type.clone(),
+                location.clone(),
+                data_hash.clone(),
+                note.clone(),
+                recorded_at,
+            ),
+        );
+        verify_device_payload(&env, &public_key, &payload, &signature, recorded_at)?;
+
+        advance_state(&env, &product, &event_type)?;
+
+        let event = append_event(
+            &env,
+            &product_id,
+            device.sponsor,
+            location,
+            event_type,
+            data_hash,
+            note,
+            Map::new(&env),
+        );
+        storage::put_event_device(&env, event.event_id, &public_key);
+        Ok(event.event_id)
     }
 
-<<<<<<< nnennaokoye/chainlogistics
-    pub fn transfer_product(env: Env, owner: Address, product_id: String, new_owner: Address) -> Result<(), Error> {
-        require_not_paused(&env)?;
-=======
-    /// Check whether an actor is authorized to add events to a product.
-    pub fn is_authorized(env: Env, product_id: String, actor: Address) -> Result<bool, Error> {
-        let product = read_product(&env, &product_id)?;
-        if product.owner == actor {
-            return Ok(true);
-        }
-        Ok(storage::is_authorized(&env, &product_id, &actor))
+    /// Get the device key that signed an event, if it came from a device.
+    pub fn get_event_device(env: Env, event_id: u64) -> Result<BytesN<32>, Error> {
+        storage::get_event_device(&env, event_id).ok_or(Error::DeviceNotRegistered)
     }
 }
 
 #[contractimpl]
 impl ChainLogisticsContract {
-    // --- Authorization & Transfer ---
+    // --- Certification Registry ---
 
-    /// Transfer product ownership to a new address.
-    pub fn transfer_product(
+    /// Add or update a certifying body and the certification types it may issue.
+    pub fn set_cert_issuer(
         env: Env,
-        owner: Address,
-        product_id: String,
-        new_owner: Address,
+        admin: Address,
+        issuer: Address,
+        name: String,
+        cert_types: Vec<Symbol>,
     ) -> Result<(), Error> {
->>>>>>> main
-        let mut product = read_product(&env, &product_id)?;
-        require_owner(&product, &owner)?;
-
-        new_owner.require_auth();
-
-        // Remove old owner's explicit auth entry, add new owner's
-        storage::set_auth(&env, &product_id, &owner, false);
-        storage::set_auth(&env, &product_id, &new_owner, true);
-
-<<<<<<< nnennaokoye/chainlogistics
-    pub fn set_product_active(env: Env, owner: Address, product_id: String, active: bool) -> Result<(), Error> {
-        require_not_paused(&env)?;
-        let mut product = read_product(&env, &product_id)?;
-        require_owner(&product, &owner)?;
-        product.active = active;
-=======
-        product.owner = new_owner.clone();
->>>>>>> main
-        write_product(&env, &product);
-
-        env.events().publish(
-            (Symbol::new(&env, "product_transferred"), product_id),
-            (owner, new_owner),
-        );
+        require_admin(&env, &admin)?;
+        apply_admin_action(
+            &env,
+            &admin,
+            AdminAction::SetCertIssuer(issuer, name, cert_types),
+        )
+    }
 
-        Ok(())
+    /// Remove a certifying body from the registry. Attestations it already
+    /// issued stay valid until they expire or it revokes them.
+    pub fn remove_cert_issuer(env: Env, admin: Address, issuer: Address) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        apply_admin_action(&env, &admin, AdminAction::RemoveCertIssuer(issuer))
     }
-}
 
-#[contractimpl]
-impl ChainLogisticsContract {
-    // --- Tracking Events ---
+    pub fn get_cert_issuer(env: Env, issuer: Address) -> Result<CertIssuer, Error> {
+        storage::get_cert_issuer(&env, &issuer).ok_or(Error::IssuerNotRegistered)
+    }
 
-<<<<<<< nnennaokoye/chainlogistics
-    pub fn add_tracking_event(env: Env, actor: Address, product_id: String, event_type: Symbol, data_hash: BytesN<32>, note: String) -> Result<u64, Error> {
-        require_not_paused(&env)?;
-=======
-    /// Add a tracking event to a product.
-    pub fn add_tracking_event(
+    /// Attest that a product holds a certification, backed by an off-chain
+    /// document identified by `doc_hash`. Returns the attestation ID.
+    pub fn attest_certification(
         env: Env,
-        actor: Address,
+        issuer: Address,
         product_id: String,
-        event_type: Symbol,
-        location: String,
-        data_hash: BytesN<32>,
-        note: String,
-        metadata: Map<Symbol, String>,
+        cert_type: Symbol,
+        doc_hash: BytesN<32>,
+        expires_at: u64,
     ) -> Result<u64, Error> {
->>>>>>> main
         let product = read_product(&env, &product_id)?;
-        require_can_add_event(&env, &product_id, &product, &actor)?;
-
-        // Validate metadata limits
-        const MAX_METADATA_FIELDS: u32 = 20;
-        const MAX_METADATA_VALUE_LEN: u32 = 256;
+        require_product_unpaused(&env, PauseScope::All, &product)?;
+        issuer.require_auth();
 
-        if metadata.len() > MAX_METADATA_FIELDS {
-            return Err(Error::TooManyCustomFields);
+        let record = storage::get_cert_issuer(&env, &issuer).ok_or(Error::IssuerNotRegistered)?;
+        if !record.active {
+            return Err(Error::IssuerNotRegistered);
+        }
+        if !record.cert_types.contains(&cert_type) {
+            return Err(Error::CertificationTypeNotAllowed);
         }
 
-        let meta_keys = metadata.keys();
-        for i in 0..meta_keys.len() {
-            let k = meta_keys.get_unchecked(i);
-            let v = metadata.get_unchecked(k);
-            if !validation::max_len(&v, MAX_METADATA_VALUE_LEN) {
-                return Err(Error::CustomFieldValueTooLong);
-            }
+        let now = env.ledger().timestamp();
+        if expires_at <= now {
+            return Err(Error::InvalidInput);
         }
 
-        let event_id = storage::next_event_id(&env);
-        let event = TrackingEvent {
-            event_id,
+        let attestation = Attestation {
+            attestation_id: storage::next_attestation_id(&env),
+            issuer,
             product_id: product_id.clone(),
-            actor,
-            timestamp: env.ledger().timestamp(),
-            event_type,
-            data_hash,
-            note,
+            cert_type,
+            doc_hash,
+            issued_at: now,
+            expires_at,
+            revoked: false,
         };
+        storage::put_attestation(&env, &attestation);
+        storage::add_product_attestation(&env, &product_id, attestation.attestation_id);
+
+        env.events().publish(
+            (Symbol::new(&env, "certification_attested"), product_id),
+            attestation.clone(),
+        );
+
+        Ok(attestation.attestation_id)
+    }
 
-        storage::put_event(&env, &event);
+    /// Revoke an attestation. Only the issuer that made it may revoke it.
+    pub fn revoke_certification(
+        env: Env,
+        issuer: Address,
+        attestation_id: u64,
+    ) -> Result<(), Error> {
+        let mut attestation =
+            storage::get_attestation(&env, attestation_id).ok_or(Error::AttestationNotFound)?;
+        issuer.require_auth();
 
-        // Append to the product's ordered event list
-        let mut ids = storage::get_product_event_ids(&env, &product_id);
-        ids.push_back(event_id);
-        storage::put_product_event_ids(&env, &product_id, &ids);
+        if attestation.issuer != issuer {
+            return Err(Error::Unauthorized);
+        }
+        if attestation.revoked {
+            return Err(Error::AttestationAlreadyRevoked);
+        }
 
-        // Index by event type for efficient type-based filtering
-        storage::index_event_by_type(&env, &product_id, &event_type, event_id);
+        attestation.revoked = true;
+        storage::put_attestation(&env, &attestation);
 
         env.events().publish(
             (
-                Symbol::new(&env, "tracking_event"),
-                product_id.clone(),
-                event_id,
+                Symbol::new(&env, "certification_revoked"),
+                attestation.product_id,
             ),
-            event.clone(),
+            attestation_id,
         );
 
-        Ok(event_id)
+        Ok(())
     }
 
-<<<<<<< nnennaokoye/chainlogistics
-    pub fn register_products_batch(env: Env, owner: Address, inputs: Vec<ProductRegistrationInput>) -> Result<Vec<Product>, Error> {
-        require_not_paused(&env)?;
-        const MAX_BATCH: u32 = 100;
-=======
-    /// Get a single tracking event by its numeric ID.
-    pub fn get_event(env: Env, event_id: u64) -> Result<TrackingEvent, Error> {
-        storage::get_event(&env, event_id).ok_or(Error::EventNotFound)
+    pub fn get_attestation(env: Env, attestation_id: u64) -> Result<Attestation, Error> {
+        storage::get_attestation(&env, attestation_id).ok_or(Error::AttestationNotFound)
     }
->>>>>>> main
 
-    /// Get all events for a product with cursor-based pagination.
-    pub fn get_product_events(
+    /// Attestations for a product that are neither revoked nor expired.
+    pub fn get_valid_certifications(
         env: Env,
         product_id: String,
-        offset: u64,
-        limit: u64,
-    ) -> Result<TrackingEventPage, Error> {
+    ) -> Result<Vec<Attestation>, Error> {
         let _ = read_product(&env, &product_id)?;
 
-        let all_ids = storage::get_product_event_ids(&env, &product_id);
-        let total_count = all_ids.len() as u64;
-
-        let event_ids =
-            storage::get_product_event_ids_paginated(&env, &product_id, offset, limit);
-
-        let mut events = Vec::new(&env);
-        for i in 0..event_ids.len() {
-            let eid = event_ids.get_unchecked(i);
-            if let Some(event) = storage::get_event(&env, eid) {
-                events.push_back(event);
+        let now = env.ledger().timestamp();
+        let count = storage::get_product_attestation_count(&env, &product_id);
+        let mut valid = Vec::new(&env);
+        for i in 1..=count {
+            let id = storage::get_product_attestation(&env, &product_id, i);
+            if let Some(attestation) = id.and_then(|id| storage::get_attestation(&env, id)) {
+                if !attestation.revoked && attestation.expires_at > now {
+                    valid.push_back(attestation);
+                }
             }
         }
+        Ok(valid)
+    }
+}
 
-        let has_more = offset + (event_ids.len() as u64) < total_count;
+#[contractimpl]
+impl ChainLogisticsContract {
+    // --- Emergency Controls ---
 
-        Ok(TrackingEventPage {
-            events,
-            total_count,
-            has_more,
-        })
+    /// Appoint the emergency guardian, replacing any previous one. The
+    /// guardian can pause any scope but can never lift or shorten a pause.
+    pub fn set_guardian(env: Env, admin: Address, guardian: Address) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        apply_admin_action(&env, &admin, AdminAction::SetGuardian(guardian))
     }
 
-    /// Get events for a product filtered by event type, with pagination.
-    pub fn get_events_by_type(
-        env: Env,
-        product_id: String,
-        event_type: Symbol,
-        offset: u64,
-        limit: u64,
-    ) -> Result<TrackingEventPage, Error> {
-        let _ = read_product(&env, &product_id)?;
+    pub fn remove_guardian(env: Env, admin: Address) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        apply_admin_action(&env, &admin, AdminAction::RemoveGuardian)
+    }
 
-        let total_count =
-            storage::get_event_count_by_type(&env, &product_id, &event_type);
-        let event_ids =
-            storage::get_event_ids_by_type(&env, &product_id, &event_type, offset, limit);
+    pub fn get_guardian(env: Env) -> Option<Address> {
+        storage::get_guardian(&env)
+    }
 
-        let mut events = Vec::new(&env);
-        for i in 0..event_ids.len() {
-            let eid = event_ids.get_unchecked(i);
-            if let Some(event) = storage::get_event(&env, eid) {
-                events.push_back(event);
+    /// Pause writes in `scope` for `duration` seconds, or until unpaused when
+    /// `duration` is 0. Reads are never paused. Callable by the admin (unless
+    /// a council governs) or the guardian. Pausing a scope again replaces its
+    /// reason and expiry, but the guardian may only do so with a pause that
+    /// lasts at least as long.
+    pub fn pause_scope(
+        env: Env,
+        caller: Address,
+        scope: PauseScope,
+        reason: String,
+        duration: u64,
+    ) -> Result<PauseRecord, Error> {
+        caller.require_auth();
+        let is_admin = is_sole_admin(&env, &caller);
+        let is_guardian = storage::get_guardian(&env) == Some(caller.clone());
+        if !is_admin && !is_guardian {
+            return Err(Error::Unauthorized);
+        }
+        validate_pause_reason(&reason)?;
+
+        if !is_admin && is_scope_paused(&env, &scope) {
+            let current = storage::get_pause(&env, &scope).unwrap();
+            let expires_at = env.ledger().timestamp().saturating_add(duration);
+            let lasts_as_long =
+                duration == 0 || (current.expires_at != 0 && expires_at >= current.expires_at);
+            if !lasts_as_long {
+                return Err(Error::Unauthorized);
             }
         }
 
-        let has_more = offset + (event_ids.len() as u64) < total_count;
-
-        Ok(TrackingEventPage {
-            events,
-            total_count,
-            has_more,
-        })
+        Ok(record_pause(&env, &caller, scope, reason, duration))
     }
 
-    /// Get events filtered by time range with pagination.
-    pub fn get_events_by_time_range(
-        env: Env,
-        product_id: String,
-        start_time: u64,
-        end_time: u64,
-        offset: u64,
-        limit: u64,
-    ) -> Result<TrackingEventPage, Error> {
-        let _ = read_product(&env, &product_id)?;
-
-        let all_ids = storage::get_product_event_ids(&env, &product_id);
-        let mut matching_ids = Vec::new(&env);
+    /// Lift the pause on `scope`. Admin only.
+    pub fn unpause_scope(env: Env, admin: Address, scope: PauseScope) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        apply_admin_action(&env, &admin, AdminAction::Unpause(scope))
+    }
 
-        for i in 0..all_ids.len() {
-            let eid = all_ids.get_unchecked(i);
-            if let Some(event) = storage::get_event(&env, eid) {
-                if event.timestamp >= start_time && event.timestamp <= end_time {
-                    matching_ids.push_back(eid);
-                }
-            }
+    /// The pause in force on `scope`. Expired pauses are not returned.
+    pub fn get_pause(env: Env, scope: PauseScope) -> Result<PauseRecord, Error> {
+        if !is_scope_paused(&env, &scope) {
+            return Err(Error::PauseNotFound);
         }
+        storage::get_pause(&env, &scope).ok_or(Error::PauseNotFound)
+    }
 
-        let total_count = matching_ids.len() as u64;
-
-        let mut events = Vec::new(&env);
-        let start = offset as u32;
-        let end = ((offset + limit) as u32).min(matching_ids.len());
-
-        for i in start..end {
-            let eid = matching_ids.get_unchecked(i);
-            if let Some(event) = storage::get_event(&env, eid) {
-                events.push_back(event);
+    /// Every pause currently in force.
+    pub fn get_active_pauses(env: Env) -> Vec<PauseRecord> {
+        let mut active = Vec::new(&env);
+        for scope in storage::get_pause_scopes(&env).iter() {
+            if is_scope_paused(&env, &scope) {
+                active.push_back(storage::get_pause(&env, &scope).unwrap());
             }
         }
+        active
+    }
+}
 
-        let has_more = offset + (events.len() as u64) < total_count;
+#[contractimpl]
+impl ChainLogisticsContract {
+    // --- Admin Council ---
 
-        Ok(TrackingEventPage {
-            events,
-            total_count,
-            has_more,
-        })
+    /// Hand admin rights to a council of `signers`, `threshold` of whom must
+    /// approve each admin action. From then on the single admin's calls fail
+    /// with `CouncilActive`; changes go through `propose_admin_action`.
+    pub fn set_admin_council(
+        env: Env,
+        admin: Address,
+        signers: Vec<Address>,
+        threshold: u32,
+    ) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        apply_admin_action(&env, &admin, AdminAction::SetCouncil(signers, threshold))
     }
 
-<<<<<<< nnennaokoye/chainlogistics
-    pub fn add_tracking_events_batch(env: Env, actor: Address, inputs: Vec<TrackingEventInput>) -> Result<Vec<u64>, Error> {
-        require_not_paused(&env)?;
-        const MAX_BATCH: u32 = 200;
-=======
-    /// Get events with composite filter (type + time range + location).
-    pub fn get_filtered_events(
-        env: Env,
-        product_id: String,
-        filter: TrackingEventFilter,
-        offset: u64,
-        limit: u64,
-    ) -> Result<TrackingEventPage, Error> {
-        let _ = read_product(&env, &product_id)?;
+    pub fn get_admin_council(env: Env) -> Result<AdminCouncil, Error> {
+        storage::get_admin_council(&env).ok_or(Error::NotInitialized)
+    }
 
-        let all_ids = storage::get_product_event_ids(&env, &product_id);
-        let mut matching_ids = Vec::new(&env);
+    /// Switch the contract to an already uploaded Wasm. Admin only; a council
+    /// upgrades through an `Upgrade` proposal instead.
+    pub fn upgrade(env: Env, admin: Address, wasm_hash: BytesN<32>) -> Result<(), Error> {
+        require_admin(&env, &admin)?;
+        apply_admin_action(&env, &admin, AdminAction::Upgrade(wasm_hash))
+    }
 
-        let empty_sym = Symbol::new(&env, "");
-        let empty_loc = String::from_str(&env, "");
+    /// Open a proposal for `action`, counting the proposer's approval. It can
+    /// be approved and executed for `PROPOSAL_LIFETIME` seconds; council
+    /// changes and upgrades only after the first `COUNCIL_TIMELOCK` of them.
+    pub fn propose_admin_action(
+        env: Env,
+        signer: Address,
+        action: AdminAction,
+    ) -> Result<AdminProposal, Error> {
+        require_council_signer(&env, &signer)?;
+
+        let now = env.ledger().timestamp();
+        let proposal = AdminProposal {
+            proposal_id: storage::next_admin_proposal_id(&env),
+            ready_at: now.saturating_add(timelock_for(&action)),
+            action,
+            proposer: signer.clone(),
+            approvals: Vec::from_array(&env, [signer.clone()]),
+            created_at: now,
+            expires_at: now.saturating_add(PROPOSAL_LIFETIME),
+            status: ProposalStatus::Pending,
+        };
+        storage::put_admin_proposal(&env, &proposal);
 
-        for i in 0..all_ids.len() {
-            let eid = all_ids.get_unchecked(i);
-            if let Some(event) = storage::get_event(&env, eid) {
-                let mut matches = true;
+        env.events().publish(
+            (Symbol::new(&env, "proposal_created"), signer),
+            proposal.clone(),
+        );
 
-                // Event type filter
-                if filter.event_type != empty_sym && event.event_type != filter.event_type {
-                    matches = false;
-                }
-                // Time lower bound
-                if filter.start_time > 0 && event.timestamp < filter.start_time {
-                    matches = false;
-                }
-                // Time upper bound
-                if filter.end_time < u64::MAX && event.timestamp > filter.end_time {
-                    matches = false;
-                }
-                // Location filter
-                if filter.location != empty_loc && event.location != filter.location {
-                    matches = false;
-                }
->>>>>>> main
+        Ok(proposal)
+    }
 
-                if matches {
-                    matching_ids.push_back(eid);
-                }
-            }
+    pub fn approve_admin_proposal(
+        env: Env,
+        signer: Address,
+        proposal_id: u64,
+    ) -> Result<AdminProposal, Error> {
+        require_council_signer(&env, &signer)?;
+        let mut proposal = read_open_proposal(&env, proposal_id)?;
+        if proposal.approvals.contains(&signer) {
+            return Err(Error::AlreadyApproved);
         }
 
-        let total_count = matching_ids.len() as u64;
+        proposal.approvals.push_back(signer.clone());
+        storage::put_admin_proposal(&env, &proposal);
 
-        let mut events = Vec::new(&env);
-        let start = offset as u32;
-        let end = ((offset + limit) as u32).min(matching_ids.len());
+        env.events().publish(
+            (Symbol::new(&env, "proposal_approved"), signer),
+            proposal_id,
+        );
 
-        for i in start..end {
-            let eid = matching_ids.get_unchecked(i);
-            if let Some(event) = storage::get_event(&env, eid) {
-                events.push_back(event);
-            }
+        Ok(proposal)
+    }
+
+    /// Run an approved proposal. Any signer may execute it once the threshold
+    /// is met by current signers and its timelock has run out.
+    pub fn execute_admin_proposal(
+        env: Env,
+        signer: Address,
+        proposal_id: u64,
+    ) -> Result<AdminProposal, Error> {
+        let council = require_council_signer(&env, &signer)?;
+        let mut proposal = read_open_proposal(&env, proposal_id)?;
+        if count_approvals(&council, &proposal) < council.threshold {
+            return Err(Error::ProposalNotReady);
+        }
+        if env.ledger().timestamp() < proposal.ready_at {
+            return Err(Error::ProposalNotReady);
         }
 
-        let has_more = offset + (events.len() as u64) < total_count;
+        apply_admin_action(&env, &signer, proposal.action.clone())?;
+        proposal.status = ProposalStatus::Executed;
+        storage::put_admin_proposal(&env, &proposal);
 
-        Ok(TrackingEventPage {
-            events,
-            total_count,
-            has_more,
-        })
+        env.events().publish(
+            (Symbol::new(&env, "proposal_executed"), signer),
+            proposal_id,
+        );
+
+        Ok(proposal)
     }
 
-    // ═══════════════════════════════════════════════════════════════════════
-    // EVENT COUNT HELPERS
-    // ═══════════════════════════════════════════════════════════════════════
+    /// Withdraw a pending proposal. Only its proposer may cancel it.
+    pub fn cancel_admin_proposal(env: Env, signer: Address, proposal_id: u64) -> Result<(), Error> {
+        require_council_signer(&env, &signer)?;
+        let mut proposal = read_open_proposal(&env, proposal_id)?;
+        if proposal.proposer != signer {
+            return Err(Error::Unauthorized);
+        }
 
-    /// Get total event count for a product.
-    pub fn get_event_count(env: Env, product_id: String) -> Result<u64, Error> {
-        let _ = read_product(&env, &product_id)?;
-        let ids = storage::get_product_event_ids(&env, &product_id);
-        Ok(ids.len() as u64)
+        proposal.status = ProposalStatus::Cancelled;
+        storage::put_admin_proposal(&env, &proposal);
+
+        env.events().publish(
+            (Symbol::new(&env, "proposal_cancelled"), signer),
+            proposal_id,
+        );
+
+        Ok(())
     }
 
-    /// Get event count for a specific event type on a product.
-    pub fn get_event_count_by_type(
-        env: Env,
-        product_id: String,
-        event_type: Symbol,
-    ) -> Result<u64, Error> {
-        let _ = read_product(&env, &product_id)?;
-        Ok(storage::get_event_count_by_type(&env, &product_id, &event_type))
+    pub fn get_admin_proposal(env: Env, proposal_id: u64) -> Result<AdminProposal, Error> {
+        storage::get_admin_proposal(&env, proposal_id).ok_or(Error::ProposalNotFound)
     }
 }
@@ -1,5 +1,8 @@
 use soroban_sdk::contracterror;
 
+/// Contract error codes. A contract spec holds at most 50 error cases, so
+/// prefer `InvalidInput` for malformed arguments over a dedicated variant.
+/// Codes of folded variants are left unused rather than reassigned.
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
@@ -17,27 +20,18 @@ pub enum Error {
     InvalidOrigin = 8,
     InvalidCategory = 9,
 
-    ProductIdTooLong = 10,
-    ProductNameTooLong = 11,
-    OriginTooLong = 12,
-    CategoryTooLong = 13,
-    DescriptionTooLong = 14,
-
     TooManyTags = 15,
-    TagTooLong = 16,
     TooManyCertifications = 17,
     TooManyMediaHashes = 18,
 
     TooManyCustomFields = 19,
     CustomFieldValueTooLong = 20,
 
-    EmptyBatch = 21,
-    BatchTooLarge = 22,
-    DuplicateProductIdInBatch = 23,
-
+    // --- Admin ---
     NotInitialized = 24,
     AlreadyInitialized = 25,
     ContractPaused = 26,
+
     // --- Lifecycle ---
     /// Attempted to add a tracking event to a deactivated product.
     ProductDeactivated = 21,
@@ -45,4 +39,78 @@ pub enum Error {
     DeactivationReasonRequired = 22,
     /// Attempted to reactivate a product that is already active.
     ProductAlreadyActive = 23,
-}
\ No newline at end of file
+
+    // --- Ownership transfer ---
+    /// No pending transfer exists for the product.
+    TransferNotFound = 27,
+    /// The pending transfer passed its expiry timestamp before acceptance.
+    TransferExpired = 28,
+    /// Transfer expiry must lie in the future.
+    InvalidTransferExpiry = 29,
+
+    // --- Roles ---
+    /// The actor's role does not allow logging this event type.
+    EventTypeNotPermitted = 30,
+    /// The actor holds no role on the product.
+    ActorNotFound = 31,
+    /// Grant window is empty or already over.
+    InvalidGrantWindow = 32,
+
+    // --- Recalls ---
+    /// Recalled products only accept return and disposal events.
+    ProductRecalled = 33,
+    /// The recall scope matched no products.
+    RecallScopeEmpty = 34,
+    RecallNotFound = 35,
+
+    // --- Lifecycle state machine ---
+    /// The event type is not an allowed next state for the product.
+    InvalidStateTransition = 36,
+
+    // --- Telemetry ---
+    /// The device key is not in the device registry.
+    DeviceNotRegistered = 37,
+    /// Reading is not newer than the device's last accepted reading.
+    StaleReading = 38,
+    /// No telemetry thresholds are configured for the product.
+    ThresholdsNotSet = 40,
+
+    // --- Device registry ---
+    DeviceAlreadyRegistered = 41,
+    DeviceRevoked = 42,
+    /// The product is not in the device's scope.
+    DeviceOutOfScope = 43,
+
+    // --- Certifications ---
+    /// Caller is not an active certifying body in the registry.
+    IssuerNotRegistered = 44,
+    /// The issuer is not accredited for this certification type.
+    CertificationTypeNotAllowed = 45,
+    AttestationNotFound = 46,
+    AttestationAlreadyRevoked = 47,
+
+    // --- Event proofs ---
+    /// The product's event tree never had the requested size.
+    MerkleRootNotFound = 48,
+
+    // --- Metadata history ---
+    VersionNotFound = 49,
+
+    // --- Event queries ---
+    /// The cursor is malformed or no longer matches the product's events.
+    InvalidCursor = 50,
+
+    // --- Emergency controls ---
+    /// No pause is in force for the scope.
+    PauseNotFound = 51,
+
+    // --- Admin council ---
+    /// A council holds admin rights; the call must go through a proposal.
+    CouncilActive = 52,
+    ProposalNotFound = 53,
+    /// The proposal was executed, cancelled or has expired.
+    ProposalClosed = 54,
+    AlreadyApproved = 55,
+    /// Too few current signers have approved, or the timelock has not run out.
+    ProposalNotReady = 56,
+}
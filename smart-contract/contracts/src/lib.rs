@@ -1,15 +1,17 @@
 #![allow(unexpected_cfgs)]
+// Contract entry points take their arguments flat, as clients call them.
+#![allow(clippy::too_many_arguments)]
 #![no_std]
 
 mod contract;
-mod types;
-mod storage;
 mod error;
+mod storage;
+mod types;
 mod validation;
 
 #[cfg(test)]
 mod test;
 
 pub use contract::*;
+pub use error::*;
 pub use types::*;
-pub use error::*;
\ No newline at end of file
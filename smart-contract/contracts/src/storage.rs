@@ -1,45 +1,100 @@
-use soroban_sdk::{contracttype, Address, Env, String, Symbol, Vec};
-
-use crate::{Product, TrackingEvent};
-
-/// Storage keys for persistent data on the blockchain.
-/// 
-/// Uses Soroban's persistent storage API which ensures data persists
-/// across contract invocations and ledger entries.
-#[contracttype]
-#[derive(Clone)]
-pub enum DataKey {
-    /// Stores a Product struct by product ID
-    Product(String),
-    /// Stores a vector of event IDs associated with a product
-    ProductEventIds(String),
-    ProductEventTimestamps(String),
-    ProductEventIdsByType(String, Symbol),
-    ProductEventIdsByActor(String, Address),
-
-    Admin,
-    Paused,
-
-    /// Stores a TrackingEvent by event ID
-    Event(u64),
-    /// Sequence counter for generating unique event IDs
-    EventSeq,
-
-    /// Authorization mapping: (product_id, actor_address) -> bool
-    Auth(String, Address),
-}
-
-/// Checks if a product exists in persistent storage.
-/// 
-/// # Arguments
-/// * `env` - The contract environment
-/// * `product_id` - The unique identifier for the product
-/// 
-/// # Returns
-/// `true` if the product exists, `false` otherwise
-use soroban_sdk::{Address, Env, String, Symbol, Vec};
+use core::cmp::Ordering;
+
+use soroban_sdk::{Address, BytesN, Env, IntoVal, String, Symbol, TryFromVal, Val, Vec};
+
+use crate::types::{
+    ActorGrant, ActorRole, AdminCouncil, AdminHandover, AdminProposal, Attestation, CertIssuer,
+    DataKey, Device, EventMerkleTree, GovernanceKey, Lifecycle, LineageRecord, PauseRecord,
+    PauseScope, PendingTransfer, Product, ProductIndex, ProductVersion, Recall, TelemetryReading,
+    TelemetryThresholds, TrackingEvent,
+};
+
+// ─── TTL ─────────────────────────────────────────────────────────────────────
+
+/// Ledgers closed per day, at roughly five seconds per ledger.
+pub const DAY_IN_LEDGERS: u32 = 17_280;
+/// Products and events read or written by the contract are kept live for at
+/// least this long, so active records never lapse into archival.
+pub const TTL_EXTEND_TO: u32 = 30 * DAY_IN_LEDGERS;
+/// Entries are only re-extended once their TTL drops below this, so
+/// back-to-back reads do not pay for an extension each time.
+pub const TTL_THRESHOLD: u32 = TTL_EXTEND_TO - DAY_IN_LEDGERS;
+
+fn bump<K: IntoVal<Env, Val>>(env: &Env, key: &K) {
+    env.storage()
+        .persistent()
+        .extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+fn bump_instance(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+}
 
-use crate::types::{DataKey, Product, TrackingEvent};
+/// Extend `key` to live at least `ledgers` more ledgers, if it exists.
+fn extend_if_present(env: &Env, key: &DataKey, ledgers: u32) {
+    if env.storage().persistent().has(key) {
+        env.storage().persistent().extend_ttl(key, ledgers, ledgers);
+    }
+}
+
+/// Read `key`, topping up its TTL when it is present.
+fn get_bumped<K, V>(env: &Env, key: &K) -> Option<V>
+where
+    K: IntoVal<Env, Val>,
+    V: TryFromVal<Env, Val>,
+{
+    let value = env.storage().persistent().get(key);
+    if value.is_some() {
+        bump(env, key);
+    }
+    value
+}
+
+/// Extend the single per-product records: the product, its event count,
+/// hash-chain head and Merkle tree, lineage record, role permissions and
+/// the counts of its actor, version, attestation and child lists. The list
+/// entries themselves are extended a page at a time by the functions below.
+pub fn extend_product_entries(env: &Env, product_id: &String, ledgers: u32) {
+    let id = product_id.clone();
+    let keys = [
+        DataKey::Product(id.clone()),
+        DataKey::ProductEventCount(id.clone()),
+        DataKey::EventChainHead(id.clone()),
+        DataKey::EventMerkle(id.clone()),
+        DataKey::ProductLineage(id.clone()),
+        DataKey::ProductActors(id.clone()),
+        DataKey::ProductVersionCount(id.clone()),
+        DataKey::ProductAttestationCount(id.clone()),
+        DataKey::ProductIndexCount(ProductIndex::Children(id.clone())),
+        DataKey::RolePermissions(id.clone(), ActorRole::Manager),
+        DataKey::RolePermissions(id.clone(), ActorRole::Carrier),
+        DataKey::RolePermissions(id.clone(), ActorRole::Warehouse),
+        DataKey::RolePermissions(id.clone(), ActorRole::Inspector),
+        DataKey::RolePermissions(id, ActorRole::Retailer),
+    ];
+    for key in keys.iter() {
+        extend_if_present(env, key, ledgers);
+    }
+    env.storage().instance().extend_ttl(ledgers, ledgers);
+}
+
+/// Extend an event and the device attribution and telemetry reading stored
+/// with it, if any.
+pub fn extend_event(env: &Env, event_id: u64, ledgers: u32) {
+    extend_if_present(env, &DataKey::Event(event_id), ledgers);
+    extend_if_present(env, &DataKey::EventDevice(event_id), ledgers);
+    extend_if_present(env, &DataKey::Telemetry(event_id), ledgers);
+}
+
+pub fn extend_event_merkle_root_at(env: &Env, product_id: &String, size: u64, ledgers: u32) {
+    extend_if_present(
+        env,
+        &DataKey::EventMerkleRootAt(product_id.clone(), size),
+        ledgers,
+    );
+}
 
 // ─── Product ────────────────────────────────────────────────────────────────
 
@@ -50,78 +105,223 @@ pub fn has_product(env: &Env, product_id: &String) -> bool {
 }
 
 pub fn put_product(env: &Env, product: &Product) {
-    env.storage()
-        .persistent()
-        .set(&DataKey::Product(product.id.clone()), product);
+    let key = DataKey::Product(product.id.clone());
+    env.storage().persistent().set(&key, product);
+    bump(env, &key);
+    bump_instance(env);
 }
 
 pub fn get_product(env: &Env, product_id: &String) -> Option<Product> {
-    env.storage()
-        .persistent()
-        .get(&DataKey::Product(product_id.clone()))
+    let key = DataKey::Product(product_id.clone());
+    let product = env.storage().persistent().get(&key);
+    if product.is_some() {
+        bump(env, &key);
+    }
+    product
 }
 
 // ─── Event IDs per product ───────────────────────────────────────────────────
 
-pub fn put_product_event_ids(env: &Env, product_id: &String, ids: &Vec<u64>) {
-    env.storage()
-        .persistent()
-        .set(&DataKey::ProductEventIds(product_id.clone()), ids);
+/// Event IDs are stored in pages of this many, so recording an event only
+/// rewrites the product's last page and readers load just the pages they
+/// need. Event timestamps are kept in parallel pages of the same size.
+pub const EVENT_ID_PAGE_SIZE: u64 = 64;
+
+fn event_id_page_key(product_id: &String, page: u64) -> DataKey {
+    DataKey::ProductEventPage(product_id.clone(), page)
 }
 
-pub fn get_product_event_ids(env: &Env, product_id: &String) -> Vec<u64> {
-    env.storage()
-        .persistent()
-        .get(&DataKey::ProductEventIds(product_id.clone()))
-        .unwrap_or(Vec::new(env))
+fn event_time_page_key(product_id: &String, page: u64) -> DataKey {
+    DataKey::ProductEventTimestamps(product_id.clone(), page)
 }
 
-pub fn put_product_event_timestamps(env: &Env, product_id: &String, ts: &Vec<u64>) {
-    env.storage()
-        .persistent()
-        .set(&DataKey::ProductEventTimestamps(product_id.clone()), ts);
+fn get_page(env: &Env, key: &DataKey) -> Vec<u64> {
+    match env.storage().persistent().get(key) {
+        Some(values) => {
+            bump(env, key);
+            values
+        }
+        None => Vec::new(env),
+    }
+}
+
+/// Append to the page at `key`, starting it afresh when `new_page` is set.
+fn push_to_page(env: &Env, key: &DataKey, value: u64, new_page: bool) {
+    let mut values = if new_page {
+        Vec::new(env)
+    } else {
+        get_page(env, key)
+    };
+    values.push_back(value);
+    env.storage().persistent().set(key, &values);
+    bump(env, key);
+}
+
+/// Number of events recorded for a product.
+pub fn get_product_event_count(env: &Env, product_id: &String) -> u64 {
+    let key = DataKey::ProductEventCount(product_id.clone());
+    match env.storage().persistent().get(&key) {
+        Some(count) => {
+            bump(env, &key);
+            count
+        }
+        None => 0,
+    }
+}
+
+/// Append an event to the product's event list, returning the new count.
+/// Ledger timestamps never go backwards, so the product's timestamp pages
+/// stay sorted.
+pub fn push_product_event(env: &Env, product_id: &String, event_id: u64, timestamp: u64) -> u64 {
+    let count = get_product_event_count(env, product_id);
+    let page = count / EVENT_ID_PAGE_SIZE;
+    let new_page = count.is_multiple_of(EVENT_ID_PAGE_SIZE);
+    push_to_page(
+        env,
+        &event_id_page_key(product_id, page),
+        event_id,
+        new_page,
+    );
+    push_to_page(
+        env,
+        &event_time_page_key(product_id, page),
+        timestamp,
+        new_page,
+    );
+
+    let count_key = DataKey::ProductEventCount(product_id.clone());
+    env.storage().persistent().set(&count_key, &(count + 1));
+    bump(env, &count_key);
+    count + 1
+}
+
+/// Every event ID for a product. Reads all of its pages; prefer
+/// `get_product_event_ids_paginated` where a window is enough.
+pub fn get_product_event_ids(env: &Env, product_id: &String) -> Vec<u64> {
+    get_product_event_ids_paginated(env, product_id, 0, u64::MAX)
 }
-/// Returns a paginated slice of event IDs for a product.
+
+/// Returns a paginated slice of event IDs for a product, loading only the
+/// pages that overlap it.
 pub fn get_product_event_ids_paginated(
     env: &Env,
     product_id: &String,
     offset: u64,
     limit: u64,
 ) -> Vec<u64> {
-    let all_ids = get_product_event_ids(env, product_id);
-    let total = all_ids.len() as u64;
-
+    let total = get_product_event_count(env, product_id);
     let mut result = Vec::new(env);
 
-    if offset >= total {
+    if offset >= total || limit == 0 {
         return result;
     }
 
-    let end = ((offset + limit) as u32).min(all_ids.len());
-    let start = offset as u32;
-
-    for i in start..end {
-        result.push_back(all_ids.get_unchecked(i));
+    let end = offset.saturating_add(limit).min(total);
+    for page in (offset / EVENT_ID_PAGE_SIZE)..=((end - 1) / EVENT_ID_PAGE_SIZE) {
+        let ids = get_page(env, &event_id_page_key(product_id, page));
+        let first = page * EVENT_ID_PAGE_SIZE;
+        let from = offset.max(first) - first;
+        let to = end.min(first + EVENT_ID_PAGE_SIZE) - first;
+        for i in from..to {
+            result.push_back(ids.get_unchecked(i as u32));
+        }
     }
 
     result
 }
 
+/// Index of the product's first event recorded at or after `time`, or the
+/// event count when there is none. Binary search over the timestamp pages
+/// by their first entry, then within the one page that can hold the answer.
+fn first_event_at_or_after(env: &Env, product_id: &String, total: u64, time: u64) -> u64 {
+    if time == 0 || total == 0 {
+        return 0;
+    }
+    let first_time =
+        |page: u64| get_page(env, &event_time_page_key(product_id, page)).get_unchecked(0);
+
+    // First page that starts at or after `time`
+    let (mut lo, mut hi) = (0u64, (total - 1) / EVENT_ID_PAGE_SIZE + 1);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if first_time(mid) < time {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    if lo == 0 {
+        return 0;
+    }
+
+    // The answer is in the page before, or is that page's end
+    let page = lo - 1;
+    let times = get_page(env, &event_time_page_key(product_id, page));
+    let (mut lo, mut hi) = (0u32, times.len());
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if times.get_unchecked(mid) < time {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    page * EVENT_ID_PAGE_SIZE + lo as u64
+}
+
+/// Positions `from..to` of the product's events recorded between `start`
+/// and `end` inclusive.
+pub fn find_product_events_in_time_range(
+    env: &Env,
+    product_id: &String,
+    start: u64,
+    end: u64,
+) -> (u64, u64) {
+    let total = get_product_event_count(env, product_id);
+    let from = first_event_at_or_after(env, product_id, total, start);
+    let to = match end.checked_add(1) {
+        Some(after) => first_event_at_or_after(env, product_id, total, after),
+        None => total,
+    };
+    (from, to.max(from))
+}
+
+/// Extend the event ID and timestamp pages holding positions `from..to` of
+/// a product's events.
+pub fn extend_product_event_pages(
+    env: &Env,
+    product_id: &String,
+    from: u64,
+    to: u64,
+    ledgers: u32,
+) {
+    for page in (from / EVENT_ID_PAGE_SIZE)..to.div_ceil(EVENT_ID_PAGE_SIZE) {
+        extend_if_present(env, &event_id_page_key(product_id, page), ledgers);
+        extend_if_present(env, &event_time_page_key(product_id, page), ledgers);
+    }
+}
+
+// ─── Events ─────────────────────────────────────────────────────────────────
+
 /// Stores a tracking event in persistent storage.
-/// 
+///
 /// # Arguments
 /// * `env` - The contract environment
 /// * `event` - The TrackingEvent to store
-// ─── Events ─────────────────────────────────────────────────────────────────
-
 pub fn put_event(env: &Env, event: &TrackingEvent) {
-    env.storage()
-        .persistent()
-        .set(&DataKey::Event(event.event_id), event);
+    let key = DataKey::Event(event.event_id);
+    env.storage().persistent().set(&key, event);
+    bump(env, &key);
+    bump_instance(env);
 }
 
 pub fn get_event(env: &Env, event_id: u64) -> Option<TrackingEvent> {
-    env.storage().persistent().get(&DataKey::Event(event_id))
+    let key = DataKey::Event(event_id);
+    let event = env.storage().persistent().get(&key);
+    if event.is_some() {
+        bump(env, &key);
+    }
+    event
 }
 
 pub fn next_event_id(env: &Env) -> u64 {
@@ -132,25 +332,61 @@ pub fn next_event_id(env: &Env) -> u64 {
         .unwrap_or(0);
     seq += 1;
     env.storage().persistent().set(&DataKey::EventSeq, &seq);
+
+    bump(env, &DataKey::EventSeq);
     seq
 }
 
+// ─── Event hash chain ────────────────────────────────────────────────────────
+
+/// Hash of the most recent event on a product, linked into the next event.
+pub fn get_event_chain_head(env: &Env, product_id: &String) -> Option<BytesN<32>> {
+    get_bumped(env, &DataKey::EventChainHead(product_id.clone()))
+}
+
+pub fn put_event_chain_head(env: &Env, product_id: &String, hash: &BytesN<32>) {
+    let key = DataKey::EventChainHead(product_id.clone());
+    env.storage().persistent().set(&key, hash);
+    bump(env, &key);
+}
+
+pub fn get_event_merkle(env: &Env, product_id: &String) -> Option<EventMerkleTree> {
+    get_bumped(env, &DataKey::EventMerkle(product_id.clone()))
+}
+
+pub fn put_event_merkle(env: &Env, product_id: &String, tree: &EventMerkleTree) {
+    let key = DataKey::EventMerkle(product_id.clone());
+    env.storage().persistent().set(&key, tree);
+    bump(env, &key);
+}
+
+/// Root the tree had when it held `size` events. Kept for every size so that
+/// a proof stays checkable after later events are appended.
+pub fn get_event_merkle_root_at(env: &Env, product_id: &String, size: u64) -> Option<BytesN<32>> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::EventMerkleRootAt(product_id.clone(), size))
+}
+
+pub fn put_event_merkle_root_at(env: &Env, product_id: &String, size: u64, root: &BytesN<32>) {
+    let key = DataKey::EventMerkleRootAt(product_id.clone(), size);
+    env.storage().persistent().set(&key, root);
+    bump(env, &key);
+}
+
 // ─── Event type index ────────────────────────────────────────────────────────
 
 /// Index an event ID under its event_type for efficient filtering.
-pub fn index_event_by_type(
-    env: &Env,
-    product_id: &String,
-    event_type: &Symbol,
-    event_id: u64,
-) {
+pub fn index_event_by_type(env: &Env, product_id: &String, event_type: &Symbol, event_id: u64) {
     let count_key = DataKey::EventTypeCount(product_id.clone(), event_type.clone());
     let mut count: u64 = env.storage().persistent().get(&count_key).unwrap_or(0);
     count += 1;
     env.storage().persistent().set(&count_key, &count);
+    bump(env, &count_key);
 
     let index_key = DataKey::EventTypeIndex(product_id.clone(), event_type.clone(), count);
     env.storage().persistent().set(&index_key, &event_id);
+    bump(env, &index_key);
 }
 
 /// Returns paginated event IDs for a given product + event_type.
@@ -161,9 +397,7 @@ pub fn get_event_ids_by_type(
     offset: u64,
     limit: u64,
 ) -> Vec<u64> {
-    let count_key = DataKey::EventTypeCount(product_id.clone(), event_type.clone());
-    let total: u64 = env.storage().persistent().get(&count_key).unwrap_or(0);
-
+    let total = get_event_count_by_type(env, product_id, event_type);
     let mut result = Vec::new(env);
 
     if offset >= total {
@@ -175,11 +409,7 @@ pub fn get_event_ids_by_type(
 
     for i in start..end {
         let index_key = DataKey::EventTypeIndex(product_id.clone(), event_type.clone(), i);
-        if let Some(event_id) = env
-            .storage()
-            .persistent()
-            .get::<DataKey, u64>(&index_key)
-        {
+        if let Some(event_id) = get_bumped(env, &index_key) {
             result.push_back(event_id);
         }
     }
@@ -190,48 +420,771 @@ pub fn get_event_ids_by_type(
 /// Returns the total number of events of a given type for a product.
 pub fn get_event_count_by_type(env: &Env, product_id: &String, event_type: &Symbol) -> u64 {
     let count_key = DataKey::EventTypeCount(product_id.clone(), event_type.clone());
-    env.storage().persistent().get(&count_key).unwrap_or(0)
+    get_counter(env, &count_key)
 }
 
-// ─── Authorization ───────────────────────────────────────────────────────────
+// ─── Actor event index ───────────────────────────────────────────────────────
+//
+// Counter + 1-based indexed keys, as for event types, kept both per product
+// and across all products so one actor's activity can be audited.
 
-pub fn set_auth(env: &Env, product_id: &String, actor: &Address, value: bool) {
-    if value {
-        env.storage()
-            .persistent()
-            .set(&DataKey::Auth(product_id.clone(), actor.clone()), &true);
-    } else {
-        env.storage()
+fn get_counter(env: &Env, count_key: &DataKey) -> u64 {
+    match env.storage().persistent().get(count_key) {
+        Some(count) => {
+            bump(env, count_key);
+            count
+        }
+        None => 0,
+    }
+}
+
+fn push_indexed(env: &Env, count_key: &DataKey, index_key: impl Fn(u64) -> DataKey, event_id: u64) {
+    let count = get_counter(env, count_key) + 1;
+    env.storage().persistent().set(count_key, &count);
+    bump(env, count_key);
+
+    let key = index_key(count);
+    env.storage().persistent().set(&key, &event_id);
+    bump(env, &key);
+}
+
+fn get_indexed(
+    env: &Env,
+    count_key: &DataKey,
+    index_key: impl Fn(u64) -> DataKey,
+    offset: u64,
+    limit: u64,
+) -> Vec<u64> {
+    let total = get_counter(env, count_key);
+    let mut result = Vec::new(env);
+
+    if offset >= total {
+        return result;
+    }
+
+    let end = offset.saturating_add(limit).min(total);
+    for i in (offset + 1)..=end {
+        let key = index_key(i);
+        if let Some(event_id) = env.storage().persistent().get::<DataKey, u64>(&key) {
+            bump(env, &key);
+            result.push_back(event_id);
+        }
+    }
+
+    result
+}
+
+/// Record `event_id` under its actor, for the product and globally.
+pub fn index_event_by_actor(env: &Env, product_id: &String, actor: &Address, event_id: u64) {
+    push_indexed(
+        env,
+        &DataKey::ActorEventCount(actor.clone()),
+        |i| DataKey::ActorEventIndex(actor.clone(), i),
+        event_id,
+    );
+    push_indexed(
+        env,
+        &DataKey::ProductActorEventCount(product_id.clone(), actor.clone()),
+        |i| DataKey::ProductActorEventIndex(product_id.clone(), actor.clone(), i),
+        event_id,
+    );
+}
+
+/// Returns paginated IDs of the events `actor` recorded on any product.
+pub fn get_event_ids_by_actor(env: &Env, actor: &Address, offset: u64, limit: u64) -> Vec<u64> {
+    get_indexed(
+        env,
+        &DataKey::ActorEventCount(actor.clone()),
+        |i| DataKey::ActorEventIndex(actor.clone(), i),
+        offset,
+        limit,
+    )
+}
+
+pub fn get_event_count_by_actor(env: &Env, actor: &Address) -> u64 {
+    get_counter(env, &DataKey::ActorEventCount(actor.clone()))
+}
+
+/// Returns paginated IDs of the events `actor` recorded on one product.
+pub fn get_product_event_ids_by_actor(
+    env: &Env,
+    product_id: &String,
+    actor: &Address,
+    offset: u64,
+    limit: u64,
+) -> Vec<u64> {
+    get_indexed(
+        env,
+        &DataKey::ProductActorEventCount(product_id.clone(), actor.clone()),
+        |i| DataKey::ProductActorEventIndex(product_id.clone(), actor.clone(), i),
+        offset,
+        limit,
+    )
+}
+
+pub fn get_product_event_count_by_actor(env: &Env, product_id: &String, actor: &Address) -> u64 {
+    get_counter(
+        env,
+        &DataKey::ProductActorEventCount(product_id.clone(), actor.clone()),
+    )
+}
+
+/// Extend the entry holding `event_id` in a counter index, and the counter.
+/// Event IDs only grow and indexes only append, so each index is sorted and
+/// the entry is found by binary search.
+fn extend_indexed(
+    env: &Env,
+    count_key: &DataKey,
+    index_key: impl Fn(u64) -> DataKey,
+    event_id: u64,
+    ledgers: u32,
+) {
+    let Some(count) = env.storage().persistent().get::<DataKey, u64>(count_key) else {
+        return;
+    };
+    extend_if_present(env, count_key, ledgers);
+
+    let (mut lo, mut hi) = (1u64, count + 1);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let Some(id) = env
+            .storage()
             .persistent()
-            .remove(&DataKey::Auth(product_id.clone(), actor.clone()));
+            .get::<DataKey, u64>(&index_key(mid))
+        else {
+            return;
+        };
+        match id.cmp(&event_id) {
+            Ordering::Less => lo = mid + 1,
+            Ordering::Greater => hi = mid,
+            Ordering::Equal => {
+                extend_if_present(env, &index_key(mid), ledgers);
+                return;
+            }
+        }
+    }
+}
+
+/// Extend `event`'s entries in its product's event-type index and in the
+/// per-product and global actor indexes.
+pub fn extend_event_index_entries(env: &Env, event: &TrackingEvent, ledgers: u32) {
+    let (product_id, event_type, actor) = (&event.product_id, &event.event_type, &event.actor);
+    extend_indexed(
+        env,
+        &DataKey::EventTypeCount(product_id.clone(), event_type.clone()),
+        |i| DataKey::EventTypeIndex(product_id.clone(), event_type.clone(), i),
+        event.event_id,
+        ledgers,
+    );
+    extend_indexed(
+        env,
+        &DataKey::ProductActorEventCount(product_id.clone(), actor.clone()),
+        |i| DataKey::ProductActorEventIndex(product_id.clone(), actor.clone(), i),
+        event.event_id,
+        ledgers,
+    );
+    extend_indexed(
+        env,
+        &DataKey::ActorEventCount(actor.clone()),
+        |i| DataKey::ActorEventIndex(actor.clone(), i),
+        event.event_id,
+        ledgers,
+    );
+}
+
+// ─── Authorization ───────────────────────────────────────────────────────────
+
+/// Store an actor's grant and keep the per-product actor index in sync.
+pub fn put_actor_grant(env: &Env, product_id: &String, grant: &ActorGrant) {
+    let key = DataKey::Auth(product_id.clone(), grant.actor.clone());
+    env.storage().persistent().set(&key, grant);
+    bump(env, &key);
+
+    let mut actors = get_product_actors(env, product_id);
+    if !actors.contains(&grant.actor) {
+        actors.push_back(grant.actor.clone());
+        put_product_actors(env, product_id, &actors);
     }
 }
 
-pub fn is_authorized(env: &Env, product_id: &String, actor: &Address) -> bool {
+/// Drop an actor's grant and remove them from the per-product actor index.
+pub fn remove_actor_grant(env: &Env, product_id: &String, actor: &Address) {
     env.storage()
         .persistent()
-        .get(&DataKey::Auth(product_id.clone(), actor.clone()))
-        .unwrap_or(false)
+        .remove(&DataKey::Auth(product_id.clone(), actor.clone()));
+
+    let mut actors = get_product_actors(env, product_id);
+    if let Some(i) = actors.first_index_of(actor) {
+        actors.remove(i);
+        put_product_actors(env, product_id, &actors);
+    }
+}
+
+pub fn get_actor_grant(env: &Env, product_id: &String, actor: &Address) -> Option<ActorGrant> {
+    get_bumped(env, &DataKey::Auth(product_id.clone(), actor.clone()))
+}
+
+pub fn extend_actor_grant(env: &Env, product_id: &String, actor: &Address, ledgers: u32) {
+    extend_if_present(
+        env,
+        &DataKey::Auth(product_id.clone(), actor.clone()),
+        ledgers,
+    );
+}
+
+/// Every actor holding a grant on a product, in the order they were added.
+pub fn get_product_actors(env: &Env, product_id: &String) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ProductActors(product_id.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+fn put_product_actors(env: &Env, product_id: &String, actors: &Vec<Address>) {
+    let key = DataKey::ProductActors(product_id.clone());
+    env.storage().persistent().set(&key, actors);
+    bump(env, &key);
+}
+
+/// Event types a role may log on a product, if the owner configured them.
+pub fn get_role_permissions(
+    env: &Env,
+    product_id: &String,
+    role: &ActorRole,
+) -> Option<Vec<Symbol>> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::RolePermissions(product_id.clone(), role.clone()))
+}
+
+pub fn put_role_permissions(
+    env: &Env,
+    product_id: &String,
+    role: &ActorRole,
+    event_types: &Vec<Symbol>,
+) {
+    let key = DataKey::RolePermissions(product_id.clone(), role.clone());
+    env.storage().persistent().set(&key, event_types);
+    bump(env, &key);
+}
+
+// ─── Lineage ─────────────────────────────────────────────────────────────────
+
+pub fn put_lineage_record(env: &Env, product_id: &String, record: &LineageRecord) {
+    let key = DataKey::ProductLineage(product_id.clone());
+    env.storage().persistent().set(&key, record);
+    bump(env, &key);
+}
+
+pub fn get_lineage_record(env: &Env, product_id: &String) -> Option<LineageRecord> {
+    get_bumped(env, &DataKey::ProductLineage(product_id.clone()))
+}
+
+pub fn add_product_child(env: &Env, parent_id: &String, child_id: &String) {
+    add_to_index(env, &ProductIndex::Children(parent_id.clone()), child_id);
+}
+
+/// Every product created from `product_id`. Lineage walks need all of them.
+pub fn get_product_children(env: &Env, product_id: &String) -> Vec<String> {
+    let index = ProductIndex::Children(product_id.clone());
+    get_index_page(env, &index, 0, u64::MAX)
+}
+
+// ─── Product indexes ─────────────────────────────────────────────────────────
+//
+// Secondary indexes from owner, category, tag, origin and parent to product
+// IDs. Counter + 1-based indexed keys, as for events, plus each product's
+// slot so it can be dropped without loading the index. Products are listed
+// in the order they joined, except that a removal moves the last product
+// into the freed slot. Deactivated products stay indexed.
+
+pub fn get_index_count(env: &Env, index: &ProductIndex) -> u64 {
+    let key = DataKey::ProductIndexCount(index.clone());
+    match env.storage().persistent().get(&key) {
+        Some(count) => {
+            bump(env, &key);
+            count
+        }
+        None => 0,
+    }
+}
+
+fn set_index_entry(env: &Env, index: &ProductIndex, slot: u64, product_id: &String) {
+    let key = DataKey::ProductIndexEntry(index.clone(), slot);
+    env.storage().persistent().set(&key, product_id);
+    bump(env, &key);
+
+    let slot_key = DataKey::ProductIndexSlot(index.clone(), product_id.clone());
+    env.storage().persistent().set(&slot_key, &slot);
+    bump(env, &slot_key);
+}
+
+pub fn add_to_index(env: &Env, index: &ProductIndex, product_id: &String) {
+    let slot_key = DataKey::ProductIndexSlot(index.clone(), product_id.clone());
+    if env.storage().persistent().has(&slot_key) {
+        return;
+    }
+
+    let count = get_index_count(env, index) + 1;
+    let count_key = DataKey::ProductIndexCount(index.clone());
+    env.storage().persistent().set(&count_key, &count);
+    bump(env, &count_key);
+    set_index_entry(env, index, count, product_id);
+}
+
+pub fn remove_from_index(env: &Env, index: &ProductIndex, product_id: &String) {
+    let slot_key = DataKey::ProductIndexSlot(index.clone(), product_id.clone());
+    let Some(slot) = env.storage().persistent().get::<DataKey, u64>(&slot_key) else {
+        return;
+    };
+    env.storage().persistent().remove(&slot_key);
+
+    let count = get_index_count(env, index);
+    let last_key = DataKey::ProductIndexEntry(index.clone(), count);
+    if slot != count {
+        let last: String = env.storage().persistent().get(&last_key).unwrap();
+        set_index_entry(env, index, slot, &last);
+    }
+    env.storage().persistent().remove(&last_key);
+
+    let count_key = DataKey::ProductIndexCount(index.clone());
+    env.storage().persistent().set(&count_key, &(count - 1));
+    bump(env, &count_key);
+}
+
+/// Up to `limit` product IDs from `index`, starting `offset` entries in.
+pub fn get_index_page(env: &Env, index: &ProductIndex, offset: u64, limit: u64) -> Vec<String> {
+    let total = get_index_count(env, index);
+    let mut result = Vec::new(env);
+
+    if offset >= total {
+        return result;
+    }
+
+    let end = offset.saturating_add(limit).min(total);
+    for i in (offset + 1)..=end {
+        let key = DataKey::ProductIndexEntry(index.clone(), i);
+        if let Some(product_id) = env.storage().persistent().get::<DataKey, String>(&key) {
+            bump(env, &key);
+            result.push_back(product_id);
+        }
+    }
+
+    result
+}
+
+/// Extend the entry at `slot` of `index` (1-based) and its slot record.
+pub fn extend_index_entry(env: &Env, index: &ProductIndex, slot: u64, ledgers: u32) {
+    let key = DataKey::ProductIndexEntry(index.clone(), slot);
+    let Some(product_id) = env.storage().persistent().get::<DataKey, String>(&key) else {
+        return;
+    };
+    extend_if_present(env, &key, ledgers);
+    let slot_key = DataKey::ProductIndexSlot(index.clone(), product_id);
+    extend_if_present(env, &slot_key, ledgers);
+}
+
+// ─── Metadata history ────────────────────────────────────────────────────────
+
+/// Store `version` and advance the product's version count to it.
+pub fn put_product_version(env: &Env, product_id: &String, version: &ProductVersion) {
+    let key = DataKey::ProductVersion(product_id.clone(), version.version);
+    env.storage().persistent().set(&key, version);
+    bump(env, &key);
+
+    let count_key = DataKey::ProductVersionCount(product_id.clone());
+    env.storage().persistent().set(&count_key, &version.version);
+    bump(env, &count_key);
+}
+
+pub fn get_product_version(env: &Env, product_id: &String, version: u32) -> Option<ProductVersion> {
+    get_bumped(env, &DataKey::ProductVersion(product_id.clone(), version))
+}
+
+pub fn extend_product_version(env: &Env, product_id: &String, version: u32, ledgers: u32) {
+    let key = DataKey::ProductVersion(product_id.clone(), version);
+    extend_if_present(env, &key, ledgers);
+}
+
+pub fn get_product_version_count(env: &Env, product_id: &String) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ProductVersionCount(product_id.clone()))
+        .unwrap_or(0)
+}
+
+// ─── Recalls ─────────────────────────────────────────────────────────────────
+
+pub fn next_recall_id(env: &Env) -> u64 {
+    let mut seq: u64 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::RecallSeq)
+        .unwrap_or(0);
+    seq += 1;
+    env.storage().persistent().set(&DataKey::RecallSeq, &seq);
+
+    bump(env, &DataKey::RecallSeq);
+    seq
+}
+
+pub fn put_recall(env: &Env, recall: &Recall) {
+    let key = DataKey::Recall(recall.recall_id);
+    env.storage().persistent().set(&key, recall);
+    bump(env, &key);
+}
+
+pub fn get_recall(env: &Env, recall_id: u64) -> Option<Recall> {
+    env.storage().persistent().get(&DataKey::Recall(recall_id))
+}
+
+/// ID of the latest recall that flagged a product.
+pub fn get_product_recall(env: &Env, product_id: &String) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ProductRecall(product_id.clone()))
+}
+
+pub fn set_product_recall(env: &Env, product_id: &String, recall_id: u64) {
+    let key = DataKey::ProductRecall(product_id.clone());
+    env.storage().persistent().set(&key, &recall_id);
+    bump(env, &key);
+}
+
+// ─── Custody lifecycle ───────────────────────────────────────────────────────
+
+pub fn get_category_lifecycle(env: &Env, category: &String) -> Option<Lifecycle> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::CategoryLifecycle(category.clone()))
+}
+
+pub fn put_category_lifecycle(env: &Env, category: &String, lifecycle: &Lifecycle) {
+    let key = DataKey::CategoryLifecycle(category.clone());
+    env.storage().persistent().set(&key, lifecycle);
+    bump(env, &key);
+}
+
+pub fn remove_category_lifecycle(env: &Env, category: &String) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::CategoryLifecycle(category.clone()));
+}
+
+pub fn get_product_state(env: &Env, product_id: &String) -> Option<Symbol> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ProductState(product_id.clone()))
+}
+
+pub fn put_product_state(env: &Env, product_id: &String, state: &Symbol) {
+    let key = DataKey::ProductState(product_id.clone());
+    env.storage().persistent().set(&key, state);
+    bump(env, &key);
+}
+
+// ─── Devices & telemetry ─────────────────────────────────────────────────────
+
+pub fn get_device(env: &Env, key: &BytesN<32>) -> Option<Device> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Device(key.clone()))
+}
+
+pub fn put_device(env: &Env, device: &Device) {
+    let key = DataKey::Device(device.public_key.clone());
+    env.storage().persistent().set(&key, device);
+    bump(env, &key);
+}
+
+/// Device key that signed an event, for events submitted by devices.
+pub fn get_event_device(env: &Env, event_id: u64) -> Option<BytesN<32>> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::EventDevice(event_id))
+}
+
+pub fn put_event_device(env: &Env, event_id: u64, key: &BytesN<32>) {
+    let event_key = DataKey::EventDevice(event_id);
+    env.storage().persistent().set(&event_key, key);
+    bump(env, &event_key);
+}
+
+pub fn get_device_last_reading(env: &Env, key: &BytesN<32>) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::DeviceLastReading(key.clone()))
+        .unwrap_or(0)
+}
+
+pub fn set_device_last_reading(env: &Env, key: &BytesN<32>, recorded_at: u64) {
+    let reading_key = DataKey::DeviceLastReading(key.clone());
+    env.storage().persistent().set(&reading_key, &recorded_at);
+    bump(env, &reading_key);
+}
+
+pub fn get_telemetry_thresholds(env: &Env, product_id: &String) -> Option<TelemetryThresholds> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::TelemetryThresholds(product_id.clone()))
+}
+
+pub fn put_telemetry_thresholds(env: &Env, product_id: &String, thresholds: &TelemetryThresholds) {
+    let key = DataKey::TelemetryThresholds(product_id.clone());
+    env.storage().persistent().set(&key, thresholds);
+    bump(env, &key);
+}
+
+pub fn put_telemetry(env: &Env, event_id: u64, reading: &TelemetryReading) {
+    let key = DataKey::Telemetry(event_id);
+    env.storage().persistent().set(&key, reading);
+    bump(env, &key);
+}
+
+pub fn get_telemetry(env: &Env, event_id: u64) -> Option<TelemetryReading> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Telemetry(event_id))
+}
+
+// ─── Certifications ──────────────────────────────────────────────────────────
+
+pub fn get_cert_issuer(env: &Env, issuer: &Address) -> Option<CertIssuer> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::CertIssuer(issuer.clone()))
+}
+
+pub fn put_cert_issuer(env: &Env, issuer: &CertIssuer) {
+    let key = DataKey::CertIssuer(issuer.issuer.clone());
+    env.storage().persistent().set(&key, issuer);
+    bump(env, &key);
+}
+
+pub fn next_attestation_id(env: &Env) -> u64 {
+    let mut seq: u64 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::AttestationSeq)
+        .unwrap_or(0);
+    seq += 1;
+    env.storage()
+        .persistent()
+        .set(&DataKey::AttestationSeq, &seq);
+
+    bump(env, &DataKey::AttestationSeq);
+    seq
+}
+
+pub fn put_attestation(env: &Env, attestation: &Attestation) {
+    let key = DataKey::Attestation(attestation.attestation_id);
+    env.storage().persistent().set(&key, attestation);
+    bump(env, &key);
+}
+
+pub fn get_attestation(env: &Env, attestation_id: u64) -> Option<Attestation> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Attestation(attestation_id))
+}
+
+pub fn add_product_attestation(env: &Env, product_id: &String, attestation_id: u64) {
+    let count_key = DataKey::ProductAttestationCount(product_id.clone());
+    let count = get_product_attestation_count(env, product_id) + 1;
+    env.storage().persistent().set(&count_key, &count);
+    bump(env, &count_key);
+
+    let key = DataKey::ProductAttestation(product_id.clone(), count);
+    env.storage().persistent().set(&key, &attestation_id);
+    bump(env, &key);
+}
+
+pub fn get_product_attestation_count(env: &Env, product_id: &String) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ProductAttestationCount(product_id.clone()))
+        .unwrap_or(0)
+}
+
+/// The ID of the product's `index`th attestation (1-based).
+pub fn get_product_attestation(env: &Env, product_id: &String, index: u64) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ProductAttestation(product_id.clone(), index))
+}
+
+/// Extend the product's `index`th attestation and the attestation itself.
+pub fn extend_product_attestation(env: &Env, product_id: &String, index: u64, ledgers: u32) {
+    let key = DataKey::ProductAttestation(product_id.clone(), index);
+    extend_if_present(env, &key, ledgers);
+    if let Some(attestation_id) = get_product_attestation(env, product_id, index) {
+        extend_if_present(env, &DataKey::Attestation(attestation_id), ledgers);
+    }
+}
+
+// ─── Pending ownership transfers ─────────────────────────────────────────────
+
+pub fn put_pending_transfer(env: &Env, transfer: &PendingTransfer) {
+    let key = DataKey::PendingTransfer(transfer.product_id.clone());
+    env.storage().persistent().set(&key, transfer);
+    bump(env, &key);
+}
+
+pub fn get_pending_transfer(env: &Env, product_id: &String) -> Option<PendingTransfer> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PendingTransfer(product_id.clone()))
+}
+
+pub fn remove_pending_transfer(env: &Env, product_id: &String) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::PendingTransfer(product_id.clone()));
 }
 
 pub fn has_admin(env: &Env) -> bool {
-    env.storage().persistent().has(&DataKey::Admin)
+    env.storage().persistent().has(&GovernanceKey::Admin)
 }
 
 pub fn get_admin(env: &Env) -> Option<Address> {
-    env.storage().persistent().get(&DataKey::Admin)
+    env.storage().persistent().get(&GovernanceKey::Admin)
 }
 
 pub fn set_admin(env: &Env, admin: &Address) {
-    env.storage().persistent().set(&DataKey::Admin, admin);
+    env.storage().persistent().set(&GovernanceKey::Admin, admin);
+
+    bump(env, &GovernanceKey::Admin);
+}
+
+pub fn get_admin_handover(env: &Env) -> Option<AdminHandover> {
+    get_bumped(env, &GovernanceKey::AdminHandover)
+}
+
+pub fn put_admin_handover(env: &Env, handover: &AdminHandover) {
+    env.storage()
+        .persistent()
+        .set(&GovernanceKey::AdminHandover, handover);
+
+    bump(env, &GovernanceKey::AdminHandover);
+}
+
+pub fn remove_admin_handover(env: &Env) {
+    env.storage()
+        .persistent()
+        .remove(&GovernanceKey::AdminHandover);
 }
 
-pub fn is_paused(env: &Env) -> bool {
-    env.storage().persistent().get(&DataKey::Paused).unwrap_or(false)
+// ─── Admin council ───────────────────────────────────────────────────────────
+
+pub fn get_admin_council(env: &Env) -> Option<AdminCouncil> {
+    let council = env.storage().persistent().get(&GovernanceKey::AdminCouncil);
+    if council.is_some() {
+        bump(env, &GovernanceKey::AdminCouncil);
+    }
+    council
+}
+
+pub fn set_admin_council(env: &Env, council: &AdminCouncil) {
+    env.storage()
+        .persistent()
+        .set(&GovernanceKey::AdminCouncil, council);
+
+    bump(env, &GovernanceKey::AdminCouncil);
+}
+
+pub fn next_admin_proposal_id(env: &Env) -> u64 {
+    let mut seq: u64 = env
+        .storage()
+        .persistent()
+        .get(&GovernanceKey::AdminProposalSeq)
+        .unwrap_or(0);
+    seq += 1;
+    env.storage()
+        .persistent()
+        .set(&GovernanceKey::AdminProposalSeq, &seq);
+
+    bump(env, &GovernanceKey::AdminProposalSeq);
+    seq
+}
+
+pub fn put_admin_proposal(env: &Env, proposal: &AdminProposal) {
+    let key = GovernanceKey::AdminProposal(proposal.proposal_id);
+    env.storage().persistent().set(&key, proposal);
+    bump(env, &key);
+}
+
+pub fn get_admin_proposal(env: &Env, proposal_id: u64) -> Option<AdminProposal> {
+    env.storage()
+        .persistent()
+        .get(&GovernanceKey::AdminProposal(proposal_id))
+}
+
+// ─── Emergency controls ──────────────────────────────────────────────────────
+
+pub fn get_guardian(env: &Env) -> Option<Address> {
+    env.storage().persistent().get(&GovernanceKey::Guardian)
+}
+
+pub fn set_guardian(env: &Env, guardian: &Address) {
+    env.storage()
+        .persistent()
+        .set(&GovernanceKey::Guardian, guardian);
+
+    bump(env, &GovernanceKey::Guardian);
+}
+
+pub fn remove_guardian(env: &Env) {
+    env.storage().persistent().remove(&GovernanceKey::Guardian);
+}
+
+/// The pause recorded for `scope`, whether or not it has expired. Kept live
+/// while it is checked, so a pause never lapses with its storage entry.
+pub fn get_pause(env: &Env, scope: &PauseScope) -> Option<PauseRecord> {
+    let key = GovernanceKey::Pause(scope.clone());
+    let record = env.storage().persistent().get(&key);
+    if record.is_some() {
+        bump(env, &key);
+    }
+    record
+}
+
+pub fn put_pause(env: &Env, record: &PauseRecord) {
+    let key = GovernanceKey::Pause(record.scope.clone());
+    env.storage().persistent().set(&key, record);
+    bump(env, &key);
+
+    let mut scopes = get_pause_scopes(env);
+    if !scopes.contains(&record.scope) {
+        scopes.push_back(record.scope.clone());
+        put_pause_scopes(env, &scopes);
+    }
+}
+
+pub fn remove_pause(env: &Env, scope: &PauseScope) {
+    env.storage()
+        .persistent()
+        .remove(&GovernanceKey::Pause(scope.clone()));
+
+    let mut scopes = get_pause_scopes(env);
+    if let Some(i) = scopes.first_index_of(scope) {
+        scopes.remove(i);
+        put_pause_scopes(env, &scopes);
+    }
+}
+
+/// Every scope with a pause recorded, expired or not.
+pub fn get_pause_scopes(env: &Env) -> Vec<PauseScope> {
+    get_bumped(env, &GovernanceKey::PauseScopes).unwrap_or_else(|| Vec::new(env))
+}
+
+fn put_pause_scopes(env: &Env, scopes: &Vec<PauseScope>) {
+    env.storage()
+        .persistent()
+        .set(&GovernanceKey::PauseScopes, scopes);
+
+    bump(env, &GovernanceKey::PauseScopes);
 }
 
-pub fn set_paused(env: &Env, paused: bool) {
-    env.storage().persistent().set(&DataKey::Paused, &paused);
 // ─── Global counters ─────────────────────────────────────────────────────────
 
 pub fn get_total_products(env: &Env) -> u64 {
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, BytesN, Map, String, Symbol, Vec};
+use soroban_sdk::{contracttype, Address, Bytes, BytesN, Map, String, Symbol, Vec};
 
 /// Information captured when a product is deactivated
 #[contracttype]
@@ -9,10 +9,60 @@ pub struct DeactInfo {
     pub deactivated_by: Address,
 }
 
+/// Role granted to an authorized actor on a product.
+///
+/// Each role may only log the event types on its allow-list; `Manager` acts
+/// on the owner's behalf and may log any event type.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ActorRole {
+    Manager,
+    Carrier,
+    Warehouse,
+    Inspector,
+    Retailer,
+}
+
+/// An actor's role on a product and the ledger-time window it applies in.
+///
+/// `valid_from = 0` and `valid_until = u64::MAX` mean the grant is unbounded
+/// on that side.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ActorGrant {
+    pub actor: Address,
+    pub role: ActorRole,
+    pub valid_from: u64,
+    pub valid_until: u64,
+}
+
+/// Latitude/longitude in micro-degrees (degrees × 1_000_000)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Coordinates {
+    pub latitude: i32,
+    pub longitude: i32,
+}
+
+/// A structured place used for product origins and tracking events.
+///
+/// `name` is a free-text label. `facility_id` holds a GS1 GLN or internal
+/// facility ID, `country` an ISO 3166-1 alpha-2 code, `geohash` an optional
+/// geohash; empty strings mean "not known".
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Location {
+    pub name: String,
+    pub facility_id: String,
+    pub country: String,
+    pub geohash: String,
+    pub coordinates: Vec<Coordinates>, // Use Vec as a safer Option alternative
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Origin {
-    pub location: String,
+    pub location: Location,
 }
 
 /// Input for product registration to avoid too many function arguments
@@ -22,7 +72,7 @@ pub struct ProductConfig {
     pub id: String,
     pub name: String,
     pub description: String,
-    pub origin_location: String,
+    pub origin: Location,
     pub category: String,
     pub tags: Vec<String>,
     pub certifications: Vec<BytesN<32>>,
@@ -48,6 +98,270 @@ pub struct Product {
     pub deactivation_info: Vec<DeactInfo>, // Use Vec as a safer Option alternative
 }
 
+/// How a product was derived from its parents
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LineageOp {
+    Split,
+    Merge,
+    Transform,
+}
+
+/// Parents a derived product was created from
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineageRecord {
+    pub op: LineageOp,
+    pub parents: Vec<String>,
+    pub created_at: u64,
+}
+
+/// A related product found while walking lineage, `depth` hops away.
+/// `op` is the operation that created the child side of the link.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineageEntry {
+    pub product_id: String,
+    pub depth: u32,
+    pub op: LineageOp,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Lineage {
+    pub product_id: String,
+    pub ancestors: Vec<LineageEntry>,
+    pub descendants: Vec<LineageEntry>,
+}
+
+/// Which products a recall campaign targets before lineage cascading
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RecallScope {
+    Products(Vec<String>),
+    Category(String),
+    Origin(String),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RecallSeverity {
+    Low,
+    Medium,
+    High,
+}
+
+/// A recall campaign and every product it flagged
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Recall {
+    pub recall_id: u64,
+    pub initiator: Address,
+    pub scope: RecallScope,
+    pub reason: String,
+    pub severity: RecallSeverity,
+    pub initiated_at: u64,
+    pub affected: Vec<String>,
+}
+
+/// Recall banner data for a single product
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecallStatus {
+    pub recalled: bool,
+    pub recall: Vec<Recall>, // Use Vec as a safer Option alternative
+}
+
+/// What a pause blocks. Every write is blocked by `All`; the others cover
+/// one kind of write, or every write to one product or category.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PauseScope {
+    All,
+    /// New products, including lineage children
+    Registrations,
+    /// Tracking events, telemetry and device events
+    EventWrites,
+    /// Ownership transfers and transfer proposals
+    Transfers,
+    Product(String),
+    Category(String),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PauseRecord {
+    pub scope: PauseScope,
+    pub reason: String,
+    pub paused_by: Address,
+    pub paused_at: u64,
+    /// Ledger timestamp the pause lifts at on its own; 0 = until unpaused
+    pub expires_at: u64,
+}
+
+/// Signers that jointly hold admin rights once installed. A proposal runs
+/// once `threshold` of them have approved it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminCouncil {
+    pub signers: Vec<Address>,
+    pub threshold: u32,
+}
+
+/// An admin operation the council votes on
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AdminAction {
+    /// Replace the signers and threshold (timelocked)
+    SetCouncil(Vec<Address>, u32),
+    /// Switch the contract to an uploaded Wasm hash (timelocked)
+    Upgrade(BytesN<32>),
+    /// Scope, reason and duration, as for `pause_scope`
+    Pause(PauseScope, String, u64),
+    Unpause(PauseScope),
+    SetGuardian(Address),
+    RemoveGuardian,
+    /// Issuer, name and certification types, as for `set_cert_issuer`
+    SetCertIssuer(Address, String, Vec<Symbol>),
+    RemoveCertIssuer(Address),
+    SetCategoryLifecycle(String, Lifecycle),
+    RemoveCategoryLifecycle(String),
+    /// Scope, reason and severity, as for `initiate_recall`
+    InitiateRecall(RecallScope, String, RecallSeverity),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProposalStatus {
+    Pending,
+    Executed,
+    Cancelled,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminProposal {
+    pub proposal_id: u64,
+    pub action: AdminAction,
+    pub proposer: Address,
+    pub approvals: Vec<Address>,
+    pub created_at: u64,
+    /// Earliest execution time: creation plus the action's timelock
+    pub ready_at: u64,
+    pub expires_at: u64,
+    pub status: ProposalStatus,
+}
+
+/// A single admin's handover to a new admin, awaiting acceptance
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminHandover {
+    pub from: Address,
+    pub to: Address,
+    pub created_at: u64,
+    /// Earliest acceptance time: creation plus `COUNCIL_TIMELOCK`
+    pub ready_at: u64,
+    pub expires_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LifecycleTransition {
+    pub from: Symbol,
+    pub to: Symbol,
+}
+
+/// Allowed custody states for a product category.
+///
+/// States are event types: logging a state event moves the product into that
+/// state. The first state event must be one of `initial`; later ones must
+/// follow a listed transition. Event types that never appear in the lifecycle
+/// are not states and are accepted in any order.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Lifecycle {
+    pub initial: Vec<Symbol>,
+    pub transitions: Vec<LifecycleTransition>,
+}
+
+/// A sensor reading in fixed-point units:
+/// temperature in centi-degrees Celsius, humidity in centi-percent,
+/// shock in milli-g, latitude/longitude in micro-degrees.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TelemetryReading {
+    pub recorded_at: u64,
+    pub temperature: i32,
+    pub humidity: u32,
+    pub shock: u32,
+    pub latitude: i32,
+    pub longitude: i32,
+}
+
+/// An unattended logger identified by its Ed25519 key.
+///
+/// Events it signs are recorded with `sponsor` as the actor, so the sponsor's
+/// permissions on each product in `scope` apply to the device as well.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Device {
+    pub public_key: BytesN<32>,
+    pub label: String,
+    pub sponsor: Address,
+    pub scope: Vec<String>,
+    pub active: bool,
+    pub registered_at: u64,
+    /// Key that replaced this one; empty until the device is rotated. A
+    /// rotated record stays, inactive, so events it signed remain attributable.
+    pub rotated_to: Vec<BytesN<32>>,
+}
+
+/// Acceptable telemetry ranges for a product; readings outside them log an
+/// `excursion` event.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TelemetryThresholds {
+    pub min_temperature: i32,
+    pub max_temperature: i32,
+    pub max_humidity: u32,
+    pub max_shock: u32,
+}
+
+/// A certifying body (organic, fair-trade, GMP, ISO...) curated by the admin
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CertIssuer {
+    pub issuer: Address,
+    pub name: String,
+    pub cert_types: Vec<Symbol>,
+    pub active: bool,
+}
+
+/// A certification an issuer attested for a product
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attestation {
+    pub attestation_id: u64,
+    pub issuer: Address,
+    pub product_id: String,
+    pub cert_type: Symbol,
+    pub doc_hash: BytesN<32>,
+    pub issued_at: u64,
+    pub expires_at: u64,
+    pub revoked: bool,
+}
+
+/// An ownership transfer offered by the current owner and awaiting acceptance
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingTransfer {
+    pub product_id: String,
+    pub from: Address,
+    pub to: Address,
+    pub created_at: u64,
+    pub expires_at: u64,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct TrackingEvent {
@@ -55,10 +369,21 @@ pub struct TrackingEvent {
     pub product_id: String,
     pub actor: Address,
     pub timestamp: u64,
+    pub location: Location,
     pub event_type: Symbol,
     pub data_hash: BytesN<32>,
     pub note: String,
     pub metadata: Map<Symbol, String>,
+    /// SHA-256 of the XDR of the product's previous event (all zeros for the first)
+    pub prev_hash: BytesN<32>,
+}
+
+/// Global product counters
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProductStats {
+    pub total_products: u64,
+    pub active_products: u64,
 }
 
 #[contracttype]
@@ -66,30 +391,211 @@ pub struct TrackingEvent {
 pub struct TrackingEventPage {
     pub events: Vec<TrackingEvent>,
     pub total_count: u64,
+    /// Set when `total_count` is only an upper bound: a filtered query that
+    /// did not fit in one page counts every event in its time range rather
+    /// than scanning them all for matches.
+    pub total_is_estimate: bool,
+    pub has_more: bool,
+    /// Pass to `get_next_events` for the following page; empty when
+    /// `has_more` is false.
+    pub next_cursor: Bytes,
+}
+
+/// The event list a query walks and the test applied to each event on it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum EventQuery {
+    All,
+    ByType(Symbol),
+    TimeRange(u64, u64),
+    Filtered(TrackingEventFilter),
+    ByActor(Address),
+    /// Events by one actor on every product; the cursor's `product_id` is
+    /// empty.
+    ActorAcrossProducts(Address),
+}
+
+/// Position of a paged event query, handed to clients as XDR bytes.
+///
+/// `end` and `total_count` are fixed when the first page is read, so events
+/// recorded later neither shift nor join the remaining pages.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EventCursor {
+    pub product_id: String,
+    pub query: EventQuery,
+    /// Index in the query's list just after the last event returned
+    pub position: u64,
+    pub end: u64,
+    pub total_count: u64,
+    pub last_event_id: u64,
+}
+
+/// Result of re-walking a product's event hash chain.
+///
+/// `broken_at` is the ID of the first event whose `prev_hash` does not match
+/// its predecessor, or 0 when the chain is intact.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EventChainReport {
+    pub valid: bool,
+    pub events_checked: u64,
+    pub broken_at: u64,
+}
+
+/// Stored state of a product's event Merkle tree.
+///
+/// Leaves are the event hashes (as linked by `prev_hash`) in recording order,
+/// hashed as in RFC 9162. Only the roots of the perfect subtrees that make up
+/// the tree are kept, largest first, so appending costs O(log n) hashes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EventMerkleTree {
+    pub size: u64,
+    pub root: BytesN<32>,
+    pub peaks: Vec<BytesN<32>>,
+}
+
+/// Merkle root over the first `size` events of a product, for checking
+/// inclusion proofs built off-chain.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EventMerkleRoot {
+    pub root: BytesN<32>,
+    pub size: u64,
+}
+
+/// The fields of a product that `update_product_metadata` may change.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProductMetadata {
+    pub name: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub media_hashes: Vec<BytesN<32>>,
+    pub custom: Map<Symbol, String>,
+}
+
+/// One entry in a product's append-only metadata history.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProductVersion {
+    /// Starts at 1 for the metadata given at registration.
+    pub version: u32,
+    pub editor: Address,
+    pub updated_at: u64,
+    /// Metadata as of this version.
+    pub metadata: ProductMetadata,
+    /// Names of the fields this version changed (empty for version 1).
+    pub changed: Vec<Symbol>,
+    /// SHA-256 of the XDR of the metadata this version replaced (all zeros
+    /// for version 1).
+    pub previous_hash: BytesN<32>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProductPage {
+    pub products: Vec<Product>,
+    pub total_count: u64,
     pub has_more: bool,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ActorPage {
+    pub actors: Vec<ActorGrant>,
+    pub total_count: u64,
+    pub has_more: bool,
+}
+
+/// Secondary indexes from a shared property to the products that have it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProductIndex {
+    Owner(Address),
+    Category(String),
+    Tag(String),
+    /// `Location.name` of the product's origin
+    Origin(String),
+    /// Products created from this one by a lineage operation
+    Children(String),
+}
+
+/// Keys for admin, council and pause state. Kept apart from `DataKey`, as a
+/// contract type can have at most 50 variants.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GovernanceKey {
+    Admin,
+    AdminCouncil,
+    AdminProposal(u64),
+    AdminProposalSeq,
+    AdminHandover,
+    Guardian,
+    Pause(PauseScope),
+    PauseScopes,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataKey {
     Product(String),
-    ProductEventIds(String),
+    ProductEventCount(String),
+    ProductEventPage(String, u64),
+    ProductEventTimestamps(String, u64),
     Event(u64),
     EventSeq,
+    EventChainHead(String),
+    EventMerkle(String),
+    EventMerkleRootAt(String, u64),
     Auth(String, Address),
+    ProductActors(String),
+    RolePermissions(String, ActorRole),
+    PendingTransfer(String),
+    ProductLineage(String),
+    ProductIndexCount(ProductIndex),
+    ProductIndexEntry(ProductIndex, u64),
+    ProductIndexSlot(ProductIndex, String),
+    ProductVersion(String, u32),
+    ProductVersionCount(String),
+    Recall(u64),
+    RecallSeq,
+    ProductRecall(String),
+    CategoryLifecycle(String),
+    ProductState(String),
+    Device(BytesN<32>),
+    DeviceLastReading(BytesN<32>),
+    EventDevice(u64),
+    TelemetryThresholds(String),
+    Telemetry(u64),
+    CertIssuer(Address),
+    Attestation(u64),
+    AttestationSeq,
+    ProductAttestationCount(String),
+    ProductAttestation(String, u64),
     EventTypeIndex(String, Symbol, u64),
     EventTypeCount(String, Symbol),
+    ActorEventIndex(Address, u64),
+    ActorEventCount(Address),
+    ProductActorEventIndex(String, Address, u64),
+    ProductActorEventCount(String, Address),
     TotalProducts,
     ActiveProducts,
 }
 
+/// One event of an `add_tracking_events_batch` call; the arguments of
+/// `add_tracking_event` without the actor
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct TrackingEventInput {
     pub product_id: String,
     pub event_type: Symbol,
+    pub location: Location,
     pub data_hash: BytesN<32>,
     pub note: String,
+    pub metadata: Map<Symbol, String>,
 }
 
 #[contracttype]
@@ -98,5 +604,9 @@ pub struct TrackingEventFilter {
     pub event_type: Symbol,
     pub start_time: u64,
     pub end_time: u64,
+    /// Exact match on `Location.name`
     pub location: String,
+    pub country: String,
+    pub facility_id: String,
+    pub geohash_prefix: String,
 }
@@ -1,4 +1,4 @@
- #![allow(dead_code)]
+#![allow(dead_code)]
 use soroban_sdk::String;
 
 pub fn non_empty(s: &String) -> bool {
@@ -8,3 +8,44 @@ pub fn non_empty(s: &String) -> bool {
 pub fn max_len(s: &String, max: u32) -> bool {
     s.len() <= max
 }
+
+/// ISO 3166-1 alpha-2 country code: exactly two ASCII uppercase letters.
+pub fn is_country_code(s: &String) -> bool {
+    if s.len() != 2 {
+        return false;
+    }
+    let mut buf = [0u8; 2];
+    s.copy_into_slice(&mut buf);
+    buf.iter().all(|b| b.is_ascii_uppercase())
+}
+
+/// Geohash: up to 12 characters from the geohash base32 alphabet.
+pub fn is_geohash(s: &String) -> bool {
+    const MAX_GEOHASH_LEN: usize = 12;
+    const ALPHABET: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+    let len = s.len() as usize;
+    if len == 0 || len > MAX_GEOHASH_LEN {
+        return false;
+    }
+    let mut buf = [0u8; MAX_GEOHASH_LEN];
+    s.copy_into_slice(&mut buf[..len]);
+    buf[..len].iter().all(|b| ALPHABET.contains(b))
+}
+
+/// Whether `s` starts with `prefix`. Only strings up to 64 bytes are compared;
+/// longer inputs never match.
+pub fn has_prefix(s: &String, prefix: &String) -> bool {
+    const MAX_CMP_LEN: usize = 64;
+
+    let len = s.len() as usize;
+    let prefix_len = prefix.len() as usize;
+    if prefix_len > len || len > MAX_CMP_LEN {
+        return false;
+    }
+    let mut a = [0u8; MAX_CMP_LEN];
+    let mut b = [0u8; MAX_CMP_LEN];
+    s.copy_into_slice(&mut a[..len]);
+    prefix.copy_into_slice(&mut b[..prefix_len]);
+    a[..prefix_len] == b[..prefix_len]
+}
@@ -0,0 +1,70 @@
+use std::fmt;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    // --- Input ---
+    /// The input is not valid JSON.
+    InvalidJson(String),
+    /// A ledger entry does not have the shape the contract writes.
+    MalformedEntry(String),
+
+    // --- Lookup ---
+    /// The snapshot holds no product with the requested ID.
+    ProductNotFound(String),
+
+    // --- Import ---
+    /// The input has no `epcisBody.eventList`.
+    NotEpcisDocument,
+    /// Events the contract would refuse. Nothing from the document should be
+    /// submitted until every rejection is fixed.
+    Rejected(Vec<Rejection>),
+}
+
+/// An EPCIS event that cannot be imported, by position in the event list.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rejection {
+    pub index: usize,
+    pub reason: RejectReason,
+}
+
+/// Why an event was rejected. Variants named after a contract `Error` fail
+/// the same check the contract would.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RejectReason {
+    /// Only ObjectEvents map onto `add_tracking_event`.
+    UnsupportedEventType(String),
+    /// The event lists no EPCs.
+    NoEpcs,
+    /// The EPC is not a product URI under the configured prefix.
+    UnrecognizedEpc(String),
+    InvalidProductId(String),
+    /// The event type is not a valid contract `Symbol`.
+    InvalidEventType(String),
+    /// A metadata key is not a valid contract `Symbol`.
+    InvalidMetadataKey(String),
+    TooManyCustomFields(usize),
+    CustomFieldValueTooLong(String),
+    InvalidLocation,
+    /// `cl:dataHash` is not 32 hex-encoded bytes.
+    InvalidDataHash,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidJson(msg) => write!(f, "invalid JSON: {msg}"),
+            Error::MalformedEntry(msg) => write!(f, "malformed ledger entry: {msg}"),
+            Error::ProductNotFound(id) => write!(f, "product not found: {id}"),
+            Error::NotEpcisDocument => write!(f, "not an EPCIS document"),
+            Error::Rejected(rejections) => {
+                write!(f, "{} event(s) rejected", rejections.len())?;
+                for r in rejections {
+                    write!(f, "\n  event {}: {:?}", r.index, r.reason)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
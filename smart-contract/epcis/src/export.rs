@@ -0,0 +1,221 @@
+use serde_json::{json, Map, Value};
+
+use crate::model::{Location, ProductHistory, TrackingEvent};
+use crate::vocab::{map_event_type, EpcisAction, EpcisEventKind};
+
+pub const EPCIS_CONTEXT: &str = "https://ref.gs1.org/standards/epcis/2.0.0/epcis-context.jsonld";
+/// JSON-LD namespace for the contract fields EPCIS has no slot for.
+pub const EXTENSION_NAMESPACE: &str = "https://chainlogistics.io/epcis/";
+const EXTENSION_PREFIX: &str = "cl";
+
+/// Knobs for how identifiers are rendered in the exported document.
+#[derive(Clone, Debug)]
+pub struct ExportOptions {
+    /// Prepended to the (percent-encoded) product ID to form its EPC URI.
+    pub product_uri_prefix: String,
+    /// Prepended to non-GLN facility IDs to form a location URI.
+    pub facility_uri_prefix: String,
+    /// Prepended to the contract event ID to form the EPCIS `eventID`.
+    pub event_uri_prefix: String,
+    /// Unix seconds written as the document's `creationDate`.
+    pub creation_date: u64,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            product_uri_prefix: "urn:chainlogistics:product:".into(),
+            facility_uri_prefix: "urn:chainlogistics:facility:".into(),
+            event_uri_prefix: "urn:chainlogistics:event:".into(),
+            creation_date: 0,
+        }
+    }
+}
+
+/// Serialize the histories of one or more products into a single EPCIS 2.0
+/// JSON-LD document. Events keep the order they have in each history.
+pub fn export_document(histories: &[ProductHistory], options: &ExportOptions) -> Value {
+    let events: Vec<Value> = histories
+        .iter()
+        .flat_map(|history| {
+            history
+                .events
+                .iter()
+                .map(move |event| export_event(history, event, options))
+        })
+        .collect();
+
+    json!({
+        "@context": [EPCIS_CONTEXT, { EXTENSION_PREFIX: EXTENSION_NAMESPACE }],
+        "type": "EPCISDocument",
+        "schemaVersion": "2.0",
+        "creationDate": format_timestamp(options.creation_date),
+        "epcisBody": { "eventList": events },
+    })
+}
+
+/// Serialize one tracking event. Lineage events become TransformationEvents,
+/// everything else an ObjectEvent on the product's EPC.
+pub fn export_event(
+    history: &ProductHistory,
+    event: &TrackingEvent,
+    options: &ExportOptions,
+) -> Value {
+    let mapping = map_event_type(&event.event_type);
+    let own_epc = product_uri(&event.product_id, options);
+
+    let mut out = Map::new();
+    match mapping.kind {
+        EpcisEventKind::ObjectEvent => {
+            out.insert("type".into(), "ObjectEvent".into());
+        }
+        EpcisEventKind::TransformationEvent => {
+            out.insert("type".into(), "TransformationEvent".into());
+        }
+    }
+    out.insert(
+        "eventID".into(),
+        format!("{}{}", options.event_uri_prefix, event.event_id).into(),
+    );
+    out.insert("eventTime".into(), format_timestamp(event.timestamp).into());
+    out.insert("eventTimeZoneOffset".into(), "+00:00".into());
+
+    match mapping.kind {
+        EpcisEventKind::ObjectEvent => {
+            out.insert("epcList".into(), json!([own_epc]));
+            out.insert("action".into(), mapping.action.as_str().into());
+        }
+        EpcisEventKind::TransformationEvent => {
+            let (inputs, outputs) = if is_derivation_event(history, event) {
+                (history.parents.clone(), vec![event.product_id.clone()])
+            } else {
+                (vec![event.product_id.clone()], history.children.clone())
+            };
+            let to_uris = |ids: Vec<String>| -> Vec<String> {
+                ids.iter().map(|id| product_uri(id, options)).collect()
+            };
+            out.insert("inputEPCList".into(), json!(to_uris(inputs)));
+            out.insert("outputEPCList".into(), json!(to_uris(outputs)));
+        }
+    }
+
+    out.insert("bizStep".into(), mapping.biz_step.into());
+    if let Some(disposition) = mapping.disposition {
+        out.insert("disposition".into(), disposition.into());
+    }
+    if let Some(read_point) = location_uri(&event.location, options) {
+        let at_rest =
+            mapping.disposition != Some("in_transit") && mapping.action != EpcisAction::Delete;
+        if at_rest && !event.location.facility_id.is_empty() {
+            out.insert("bizLocation".into(), json!({ "id": read_point.clone() }));
+        }
+        out.insert("readPoint".into(), json!({ "id": read_point }));
+    }
+
+    insert_extension(&mut out, "eventType", event.event_type.clone().into());
+    insert_extension(&mut out, "actor", event.actor.clone().into());
+    insert_extension(&mut out, "dataHash", event.data_hash.clone().into());
+    if !event.location.name.is_empty() {
+        insert_extension(&mut out, "locationName", event.location.name.clone().into());
+    }
+    if !event.note.is_empty() {
+        insert_extension(&mut out, "note", event.note.clone().into());
+    }
+    if !event.metadata.is_empty() {
+        insert_extension(&mut out, "metadata", json!(event.metadata));
+    }
+
+    Value::Object(out)
+}
+
+/// EPC URI of a product.
+pub fn product_uri(product_id: &str, options: &ExportOptions) -> String {
+    format!(
+        "{}{}",
+        options.product_uri_prefix,
+        percent_encode(product_id)
+    )
+}
+
+/// Best URI for a location: a GS1 Digital Link for 13-digit GLNs, the
+/// facility URI for other facility codes, otherwise a `geo:` URI.
+pub fn location_uri(location: &Location, options: &ExportOptions) -> Option<String> {
+    let facility = location.facility_id.as_str();
+    if facility.len() == 13 && facility.bytes().all(|b| b.is_ascii_digit()) {
+        return Some(format!("https://id.gs1.org/414/{facility}"));
+    }
+    if !facility.is_empty() {
+        return Some(format!(
+            "{}{}",
+            options.facility_uri_prefix,
+            percent_encode(facility)
+        ));
+    }
+    location.coordinates.map(|c| {
+        format!(
+            "geo:{},{}",
+            format_micro_degrees(c.latitude),
+            format_micro_degrees(c.longitude)
+        )
+    })
+}
+
+/// Format Unix seconds as an ISO 8601 UTC timestamp.
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3600,
+        (rem % 3600) / 60,
+        rem % 60
+    )
+}
+
+// ─── Internal ────────────────────────────────────────────────────────────────
+
+/// A derived product's lineage event is always its first event: the contract
+/// appends it as part of creating the child.
+fn is_derivation_event(history: &ProductHistory, event: &TrackingEvent) -> bool {
+    !history.parents.is_empty()
+        && history.events.first().map(|e| e.event_id) == Some(event.event_id)
+}
+
+fn insert_extension(out: &mut Map<String, Value>, name: &str, value: Value) {
+    out.insert(format!("{EXTENSION_PREFIX}:{name}"), value);
+}
+
+fn format_micro_degrees(value: i32) -> String {
+    let sign = if value < 0 { "-" } else { "" };
+    let abs = value.unsigned_abs();
+    format!("{sign}{}.{:06}", abs / 1_000_000, abs % 1_000_000)
+}
+
+/// Percent-encode everything outside the RFC 3986 unreserved set.
+fn percent_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for b in value.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
+
+/// Days since 1970-01-01 to (year, month, day), after Howard Hinnant's
+/// `civil_from_days`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
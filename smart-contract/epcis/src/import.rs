@@ -0,0 +1,345 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, RejectReason, Rejection};
+use crate::export::ExportOptions;
+use crate::model::{Coordinates, Location};
+use crate::vocab::{EpcisEventKind, VOCAB_TABLE};
+
+// Limits enforced by `add_tracking_event` and `validate_location` in
+// `contracts/src/contract.rs`. Keep in sync.
+pub const MAX_METADATA_FIELDS: usize = 20;
+pub const MAX_METADATA_VALUE_LEN: usize = 256;
+pub const MAX_PRODUCT_ID_LEN: usize = 64;
+pub const MAX_LOCATION_NAME_LEN: usize = 128;
+pub const MAX_FACILITY_ID_LEN: usize = 64;
+const MAX_SYMBOL_LEN: usize = 32;
+const MAX_GEOHASH_LEN: usize = 12;
+/// Most events `add_tracking_events_batch` accepts in one call.
+pub const MAX_BATCH_SIZE: usize = 50;
+
+/// Arguments for one `add_tracking_event` call, minus the signing actor; the
+/// host form of the contract's `TrackingEventInput`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ImportedEvent {
+    pub product_id: String,
+    pub event_type: String,
+    pub location: Location,
+    /// Hex-encoded 32 bytes.
+    pub data_hash: String,
+    pub note: String,
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// How EPCIS identifiers are mapped back onto contract values.
+#[derive(Clone, Debug)]
+pub struct ImportOptions {
+    /// URI prefixes, shared with the exporter so exported documents round-trip.
+    pub uris: ExportOptions,
+    /// Keep the source `eventID` and `eventTime` as `epcis_id` and
+    /// `epcis_time` metadata. Both count towards the metadata limit.
+    pub record_provenance: bool,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            uris: ExportOptions::default(),
+            record_provenance: true,
+        }
+    }
+}
+
+/// Convert an EPCIS 2.0 document into tracking events, one per EPC per
+/// ObjectEvent, in document order.
+///
+/// Every event is checked against the contract's limits first; if any fails,
+/// all rejections are returned together and no events are, so a bulk import
+/// cannot stop halfway.
+pub fn import_document(
+    document: &Value,
+    options: &ImportOptions,
+) -> Result<Vec<ImportedEvent>, Error> {
+    let events = document
+        .pointer("/epcisBody/eventList")
+        .and_then(Value::as_array)
+        .ok_or(Error::NotEpcisDocument)?;
+
+    let mut imported = Vec::new();
+    let mut rejections = Vec::new();
+    for (index, event) in events.iter().enumerate() {
+        match import_event(event, options) {
+            Ok(mut batch) => imported.append(&mut batch),
+            Err(reason) => rejections.push(Rejection { index, reason }),
+        }
+    }
+
+    if rejections.is_empty() {
+        Ok(imported)
+    } else {
+        Err(Error::Rejected(rejections))
+    }
+}
+
+/// `import_document`, split in document order into `add_tracking_events_batch`
+/// inputs of at most `MAX_BATCH_SIZE` events.
+pub fn import_batches(
+    document: &Value,
+    options: &ImportOptions,
+) -> Result<Vec<Vec<ImportedEvent>>, Error> {
+    let events = import_document(document, options)?;
+    Ok(events
+        .chunks(MAX_BATCH_SIZE)
+        .map(<[ImportedEvent]>::to_vec)
+        .collect())
+}
+
+/// Convert and validate a single EPCIS event.
+pub fn import_event(
+    event: &Value,
+    options: &ImportOptions,
+) -> Result<Vec<ImportedEvent>, RejectReason> {
+    let kind = event
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    if kind != "ObjectEvent" {
+        return Err(RejectReason::UnsupportedEventType(kind.into()));
+    }
+
+    let epcs: Vec<&str> = event
+        .get("epcList")
+        .and_then(Value::as_array)
+        .map(|list| list.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    if epcs.is_empty() {
+        return Err(RejectReason::NoEpcs);
+    }
+
+    let event_type = event_type_of(event);
+    let location = location_of(event, &options.uris);
+    let data_hash = match extension(event, "dataHash").and_then(Value::as_str) {
+        Some(hash) => hash.to_ascii_lowercase(),
+        None => content_hash(event),
+    };
+    let note = extension(event, "note")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_owned();
+    let metadata = metadata_of(event, options);
+
+    let mut out = Vec::with_capacity(epcs.len());
+    for epc in epcs {
+        let imported = ImportedEvent {
+            product_id: product_id_of(epc, &options.uris)?,
+            event_type: event_type.clone(),
+            location: location.clone(),
+            data_hash: data_hash.clone(),
+            note: note.clone(),
+            metadata: metadata.clone(),
+        };
+        validate(&imported)?;
+        out.push(imported);
+    }
+    Ok(out)
+}
+
+/// Apply the contract's checks for `add_tracking_event` on the host.
+pub fn validate(event: &ImportedEvent) -> Result<(), RejectReason> {
+    if event.product_id.is_empty() || event.product_id.len() > MAX_PRODUCT_ID_LEN {
+        return Err(RejectReason::InvalidProductId(event.product_id.clone()));
+    }
+    if !is_symbol(&event.event_type) {
+        return Err(RejectReason::InvalidEventType(event.event_type.clone()));
+    }
+    if event.data_hash.len() != 64 || !event.data_hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(RejectReason::InvalidDataHash);
+    }
+    if event.metadata.len() > MAX_METADATA_FIELDS {
+        return Err(RejectReason::TooManyCustomFields(event.metadata.len()));
+    }
+    for (key, value) in &event.metadata {
+        if !is_symbol(key) {
+            return Err(RejectReason::InvalidMetadataKey(key.clone()));
+        }
+        if value.len() > MAX_METADATA_VALUE_LEN {
+            return Err(RejectReason::CustomFieldValueTooLong(key.clone()));
+        }
+    }
+    if !is_valid_location(&event.location) {
+        return Err(RejectReason::InvalidLocation);
+    }
+    Ok(())
+}
+
+// ─── Field mapping ───────────────────────────────────────────────────────────
+
+fn extension<'a>(event: &'a Value, name: &str) -> Option<&'a Value> {
+    event.get(format!("cl:{name}"))
+}
+
+/// Strip the CBV URI forms down to the short vocabulary name.
+fn cbv_short_name(value: &str) -> &str {
+    value
+        .rsplit_once("BizStep-")
+        .or_else(|| value.rsplit_once("Disp-"))
+        .or_else(|| value.rsplit_once("bizstep:"))
+        .or_else(|| value.rsplit_once("disp:"))
+        .map_or(value, |(_, name)| name)
+}
+
+/// Our own exports carry the original `Symbol`; otherwise take the first
+/// ObjectEvent row of the vocabulary table matching bizStep and disposition,
+/// then bizStep alone. Unknown steps keep their short name.
+fn event_type_of(event: &Value) -> String {
+    if let Some(original) = extension(event, "eventType").and_then(Value::as_str) {
+        return original.into();
+    }
+    let biz_step = event
+        .get("bizStep")
+        .and_then(Value::as_str)
+        .map(cbv_short_name);
+    let disposition = event
+        .get("disposition")
+        .and_then(Value::as_str)
+        .map(cbv_short_name);
+    let Some(biz_step) = biz_step else {
+        return "observed".into();
+    };
+
+    let rows = || {
+        VOCAB_TABLE
+            .iter()
+            .filter(|(_, m)| m.kind == EpcisEventKind::ObjectEvent && m.biz_step == biz_step)
+    };
+    rows()
+        .find(|(_, m)| m.disposition == disposition)
+        .or_else(|| rows().next())
+        .map_or_else(|| biz_step.into(), |(symbol, _)| (*symbol).into())
+}
+
+fn product_id_of(epc: &str, uris: &ExportOptions) -> Result<String, RejectReason> {
+    epc.strip_prefix(uris.product_uri_prefix.as_str())
+        .and_then(percent_decode)
+        .ok_or_else(|| RejectReason::UnrecognizedEpc(epc.into()))
+}
+
+/// Read the location from `readPoint`, falling back to `bizLocation`.
+fn location_of(event: &Value, uris: &ExportOptions) -> Location {
+    let mut location = Location::default();
+    let id = ["readPoint", "bizLocation"]
+        .iter()
+        .find_map(|field| event.get(*field)?.get("id")?.as_str());
+
+    if let Some(id) = id {
+        if let Some(gln) = id.strip_prefix("https://id.gs1.org/414/") {
+            location.facility_id = gln.split('/').next().unwrap_or_default().into();
+        } else if let Some(coords) = id.strip_prefix("geo:") {
+            location.coordinates = parse_geo(coords);
+        } else if let Some(facility) = id.strip_prefix(uris.facility_uri_prefix.as_str()) {
+            location.facility_id = percent_decode(facility).unwrap_or_default();
+        } else {
+            location.facility_id = id.into();
+        }
+    }
+    if let Some(name) = extension(event, "locationName").and_then(Value::as_str) {
+        location.name = name.into();
+    }
+    location
+}
+
+fn metadata_of(event: &Value, options: &ImportOptions) -> BTreeMap<String, String> {
+    let mut metadata: BTreeMap<String, String> = extension(event, "metadata")
+        .and_then(Value::as_object)
+        .map(|fields| {
+            fields
+                .iter()
+                .map(|(k, v)| {
+                    (
+                        k.clone(),
+                        v.as_str().map_or_else(|| v.to_string(), str::to_owned),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if options.record_provenance {
+        for (field, key) in [("eventID", "epcis_id"), ("eventTime", "epcis_time")] {
+            if let Some(value) = event.get(field).and_then(Value::as_str) {
+                metadata.insert(key.into(), value.into());
+            }
+        }
+    }
+    metadata
+}
+
+/// SHA-256 of the event's JSON, so the on-chain record commits to the source.
+/// `serde_json` objects serialize with sorted keys, making this stable.
+fn content_hash(event: &Value) -> String {
+    let digest = Sha256::digest(event.to_string().as_bytes());
+    digest.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+// ─── Validation helpers ──────────────────────────────────────────────────────
+
+/// Soroban `Symbol`: up to 32 characters from `[a-zA-Z0-9_]`.
+fn is_symbol(s: &str) -> bool {
+    !s.is_empty()
+        && s.len() <= MAX_SYMBOL_LEN
+        && s.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+fn is_valid_location(location: &Location) -> bool {
+    const ALPHABET: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+    let country_ok = location.country.is_empty()
+        || (location.country.len() == 2
+            && location.country.bytes().all(|b| b.is_ascii_uppercase()));
+    let geohash_ok = location.geohash.is_empty()
+        || (location.geohash.len() <= MAX_GEOHASH_LEN
+            && location.geohash.bytes().all(|b| ALPHABET.contains(&b)));
+    let coordinates_ok = location.coordinates.is_none_or(|c| {
+        c.latitude.unsigned_abs() <= 90_000_000 && c.longitude.unsigned_abs() <= 180_000_000
+    });
+
+    location.name.len() <= MAX_LOCATION_NAME_LEN
+        && location.facility_id.len() <= MAX_FACILITY_ID_LEN
+        && country_ok
+        && geohash_ok
+        && coordinates_ok
+}
+
+/// Parse `lat,long` decimal degrees into micro-degrees.
+fn parse_geo(value: &str) -> Option<Coordinates> {
+    let (lat, long) = value.split(';').next()?.split_once(',')?;
+    let to_micro = |v: &str| -> Option<i32> {
+        let degrees: f64 = v.trim().parse().ok()?;
+        let micro = (degrees * 1_000_000.0).round();
+        (micro.abs() <= i32::MAX as f64).then_some(micro as i32)
+    };
+    Some(Coordinates {
+        latitude: to_micro(lat)?,
+        longitude: to_micro(long)?,
+    })
+}
+
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
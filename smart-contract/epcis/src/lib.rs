@@ -0,0 +1,24 @@
+//! Host-side GS1 EPCIS 2.0 export and import for ChainLogistics.
+//!
+//! Export reads product and event data from a ledger snapshot (the JSON
+//! written to `contracts/test_snapshots/`) or from contract client results and
+//! serializes it to an EPCIS 2.0 JSON-LD document. Import turns EPCIS
+//! ObjectEvents back into validated `add_tracking_event` arguments, grouped into
+//! `add_tracking_events_batch` calls.
+
+mod error;
+mod export;
+mod import;
+mod model;
+mod snapshot;
+mod vocab;
+
+#[cfg(test)]
+mod test;
+
+pub use error::*;
+pub use export::*;
+pub use import::*;
+pub use model::*;
+pub use snapshot::*;
+pub use vocab::*;
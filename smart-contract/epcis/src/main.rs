@@ -0,0 +1,67 @@
+//! `chainlogistics-epcis export <snapshot.json> [product-id ...]`
+//!     Prints an EPCIS 2.0 document for the given products, or for every
+//!     product in the snapshot when none are named.
+//!
+//! `chainlogistics-epcis import <epcis.json>`
+//!     Prints the validated events of the document as `add_tracking_events_batch`
+//!     inputs of at most 50 events each, or every rejection and a non-zero
+//!     exit status.
+
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chainlogistics_epcis::{
+    export_document, import_batches, ExportOptions, ImportOptions, Snapshot,
+};
+
+const USAGE: &str = "usage:
+  chainlogistics-epcis export <snapshot.json> [product-id ...]
+  chainlogistics-epcis import <epcis.json>";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match (args.first().map(String::as_str), args.get(1)) {
+        (Some("export"), Some(path)) => export(path, &args[2..]),
+        (Some("import"), Some(path)) => import(path),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match result {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn export(path: &str, product_ids: &[String]) -> Result<String, Box<dyn std::error::Error>> {
+    let snapshot = Snapshot::parse(&std::fs::read_to_string(path)?)?;
+    let ids = if product_ids.is_empty() {
+        snapshot.product_ids()
+    } else {
+        product_ids.to_vec()
+    };
+    let histories = ids
+        .iter()
+        .map(|id| snapshot.history(id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let options = ExportOptions {
+        creation_date: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        ..ExportOptions::default()
+    };
+    Ok(serde_json::to_string_pretty(&export_document(
+        &histories, &options,
+    ))?)
+}
+
+fn import(path: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let document = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    let batches = import_batches(&document, &ImportOptions::default())?;
+    Ok(serde_json::to_string_pretty(&batches)?)
+}
@@ -0,0 +1,66 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Host mirror of the contract's `Coordinates` (micro-degrees).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Coordinates {
+    pub latitude: i32,
+    pub longitude: i32,
+}
+
+/// Host mirror of the contract's `Location`. Empty strings mean "not set".
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Location {
+    pub name: String,
+    /// GS1 GLN or an internal facility code.
+    pub facility_id: String,
+    pub country: String,
+    pub geohash: String,
+    pub coordinates: Option<Coordinates>,
+}
+
+/// The subset of the contract's `Product` needed for EPCIS export.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Product {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub origin: Location,
+    pub owner: String,
+    pub created_at: u64,
+    pub active: bool,
+    pub category: String,
+    pub tags: Vec<String>,
+}
+
+/// Host mirror of the contract's `TrackingEvent`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrackingEvent {
+    pub event_id: u64,
+    pub product_id: String,
+    pub actor: String,
+    pub timestamp: u64,
+    pub location: Location,
+    pub event_type: String,
+    /// Hex-encoded 32-byte hash of the off-chain payload.
+    pub data_hash: String,
+    pub note: String,
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// Everything needed to export one product: its record, its events in
+/// chronological order and its direct lineage links.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProductHistory {
+    pub product: Product,
+    pub events: Vec<TrackingEvent>,
+    /// Products this one was split, merged or transformed from.
+    pub parents: Vec<String>,
+    /// Products derived from this one.
+    pub children: Vec<String>,
+}
@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::model::{Coordinates, Location, Product, ProductHistory, TrackingEvent};
+
+/// Contract data decoded from a Soroban ledger snapshot.
+///
+/// Only the `Product`, `Event` and `ProductLineage` entries and the
+/// `ProductIndexEntry` entries of each product's `Children` index are read;
+/// everything else in the snapshot is ignored.
+#[derive(Clone, Debug, Default)]
+pub struct Snapshot {
+    products: BTreeMap<String, Product>,
+    events: Vec<TrackingEvent>,
+    parents: BTreeMap<String, Vec<String>>,
+    /// Children of each product, keyed by their slot in the parent's index
+    children: BTreeMap<String, BTreeMap<u64, String>>,
+}
+
+impl Snapshot {
+    /// Parse the JSON written by `soroban_sdk` test snapshots.
+    pub fn parse(json: &str) -> Result<Self, Error> {
+        let root: Value =
+            serde_json::from_str(json).map_err(|e| Error::InvalidJson(e.to_string()))?;
+        Self::from_value(&root)
+    }
+
+    pub fn from_value(root: &Value) -> Result<Self, Error> {
+        let entries = root
+            .pointer("/ledger/ledger_entries")
+            .and_then(Value::as_array)
+            .ok_or_else(|| Error::MalformedEntry("missing ledger.ledger_entries".into()))?;
+
+        let mut snapshot = Snapshot::default();
+        for entry in entries {
+            let Some(data) = entry.pointer("/1/0/data/contract_data") else {
+                continue;
+            };
+            let Some(key) = data
+                .get("key")
+                .and_then(|k| k.get("vec"))
+                .and_then(Value::as_array)
+            else {
+                continue;
+            };
+            let Some(val) = data.get("val") else {
+                continue;
+            };
+            match key.first().and_then(as_str) {
+                Some("Product") => {
+                    let product = decode_product(val)?;
+                    snapshot.products.insert(product.id.clone(), product);
+                }
+                Some("Event") => snapshot.events.push(decode_event(val)?),
+                Some("ProductLineage") => {
+                    let id = key_id(key)?;
+                    let parents = field(val, "parents")
+                        .map(decode_strings)
+                        .unwrap_or_default();
+                    snapshot.parents.insert(id, parents);
+                }
+                Some("ProductIndexEntry") => {
+                    let Some((parent, slot)) = children_index_slot(key) else {
+                        continue;
+                    };
+                    let child = as_str(val).ok_or_else(|| {
+                        Error::MalformedEntry("product index entry without product id".into())
+                    })?;
+                    snapshot
+                        .children
+                        .entry(parent)
+                        .or_default()
+                        .insert(slot, child.to_owned());
+                }
+                _ => {}
+            }
+        }
+        snapshot.events.sort_by_key(|e| e.event_id);
+        Ok(snapshot)
+    }
+
+    /// IDs of every product in the snapshot, sorted.
+    pub fn product_ids(&self) -> Vec<String> {
+        self.products.keys().cloned().collect()
+    }
+
+    /// Collect a product's record, events and lineage links.
+    pub fn history(&self, product_id: &str) -> Result<ProductHistory, Error> {
+        let product = self
+            .products
+            .get(product_id)
+            .cloned()
+            .ok_or_else(|| Error::ProductNotFound(product_id.into()))?;
+        let events = self
+            .events
+            .iter()
+            .filter(|e| e.product_id == product_id)
+            .cloned()
+            .collect();
+        Ok(ProductHistory {
+            product,
+            events,
+            parents: self.parents.get(product_id).cloned().unwrap_or_default(),
+            children: self
+                .children
+                .get(product_id)
+                .map(|slots| slots.values().cloned().collect())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+// ─── ScVal decoding ──────────────────────────────────────────────────────────
+
+fn key_id(key: &[Value]) -> Result<String, Error> {
+    key.get(1)
+        .and_then(as_str)
+        .map(str::to_owned)
+        .ok_or_else(|| Error::MalformedEntry("storage key without product id".into()))
+}
+
+/// Parent ID and slot of a `ProductIndexEntry(ProductIndex::Children(parent), slot)`
+/// key, or `None` for entries of the other product indexes.
+fn children_index_slot(key: &[Value]) -> Option<(String, u64)> {
+    let index = key.get(1)?.get("vec")?.as_array()?;
+    if index.first().and_then(as_str) != Some("Children") {
+        return None;
+    }
+    let parent = index.get(1).and_then(as_str)?;
+    Some((parent.to_owned(), key.get(2).and_then(as_u64)?))
+}
+
+/// Unwrap any of the string-like ScVal encodings.
+fn as_str(val: &Value) -> Option<&str> {
+    ["string", "symbol", "address", "bytes"]
+        .iter()
+        .find_map(|tag| val.get(*tag))
+        .and_then(Value::as_str)
+}
+
+fn as_u64(val: &Value) -> Option<u64> {
+    ["u64", "u32"]
+        .iter()
+        .find_map(|tag| val.get(*tag))
+        .and_then(Value::as_u64)
+}
+
+fn as_i32(val: &Value) -> Option<i32> {
+    val.get("i32")
+        .and_then(Value::as_i64)
+        .and_then(|v| i32::try_from(v).ok())
+}
+
+/// Look up a field of a `#[contracttype]` struct, encoded as a symbol-keyed map.
+fn field<'a>(val: &'a Value, name: &str) -> Option<&'a Value> {
+    val.get("map")?
+        .as_array()?
+        .iter()
+        .find(|entry| entry.get("key").and_then(as_str) == Some(name))
+        .and_then(|entry| entry.get("val"))
+}
+
+fn string_field(val: &Value, name: &str) -> String {
+    field(val, name)
+        .and_then(as_str)
+        .unwrap_or_default()
+        .to_owned()
+}
+
+fn u64_field(val: &Value, name: &str) -> u64 {
+    field(val, name).and_then(as_u64).unwrap_or_default()
+}
+
+fn decode_strings(val: &Value) -> Vec<String> {
+    val.get("vec")
+        .and_then(Value::as_array)
+        .map(|items| items.iter().filter_map(as_str).map(str::to_owned).collect())
+        .unwrap_or_default()
+}
+
+/// Decode a `Location`. Snapshots written before locations were structured
+/// store a plain string, which becomes the location name.
+fn decode_location(val: &Value) -> Location {
+    if let Some(name) = as_str(val) {
+        return Location {
+            name: name.to_owned(),
+            ..Location::default()
+        };
+    }
+    let coordinates = field(val, "coordinates")
+        .and_then(|c| c.get("vec"))
+        .and_then(Value::as_array)
+        .and_then(|items| items.first())
+        .and_then(|c| {
+            Some(Coordinates {
+                latitude: field(c, "latitude").and_then(as_i32)?,
+                longitude: field(c, "longitude").and_then(as_i32)?,
+            })
+        });
+    Location {
+        name: string_field(val, "name"),
+        facility_id: string_field(val, "facility_id"),
+        country: string_field(val, "country"),
+        geohash: string_field(val, "geohash"),
+        coordinates,
+    }
+}
+
+fn decode_product(val: &Value) -> Result<Product, Error> {
+    if val.get("map").is_none() {
+        return Err(Error::MalformedEntry("product is not a map".into()));
+    }
+    let origin = field(val, "origin")
+        .and_then(|o| field(o, "location"))
+        .map(decode_location)
+        .unwrap_or_default();
+    Ok(Product {
+        id: string_field(val, "id"),
+        name: string_field(val, "name"),
+        description: string_field(val, "description"),
+        origin,
+        owner: string_field(val, "owner"),
+        created_at: u64_field(val, "created_at"),
+        active: field(val, "active")
+            .and_then(|v| v.get("bool"))
+            .and_then(Value::as_bool)
+            .unwrap_or_default(),
+        category: string_field(val, "category"),
+        tags: field(val, "tags").map(decode_strings).unwrap_or_default(),
+    })
+}
+
+fn decode_event(val: &Value) -> Result<TrackingEvent, Error> {
+    let event_id = field(val, "event_id")
+        .and_then(as_u64)
+        .ok_or_else(|| Error::MalformedEntry("event without event_id".into()))?;
+    let metadata = field(val, "metadata")
+        .and_then(|m| m.get("map"))
+        .and_then(Value::as_array)
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|entry| {
+                    let key = entry.get("key").and_then(as_str)?;
+                    let value = entry.get("val").and_then(as_str)?;
+                    Some((key.to_owned(), value.to_owned()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(TrackingEvent {
+        event_id,
+        product_id: string_field(val, "product_id"),
+        actor: string_field(val, "actor"),
+        timestamp: u64_field(val, "timestamp"),
+        location: field(val, "location")
+            .map(decode_location)
+            .unwrap_or_default(),
+        event_type: string_field(val, "event_type"),
+        data_hash: string_field(val, "data_hash"),
+        note: string_field(val, "note"),
+        metadata,
+    })
+}
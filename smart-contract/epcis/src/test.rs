@@ -0,0 +1,488 @@
+use std::collections::BTreeMap;
+
+use serde_json::json;
+
+use crate::{
+    export_document, export_event, format_timestamp, import_batches, import_document,
+    map_event_type, Coordinates, EpcisEventKind, Error, ExportOptions, ImportOptions, Location,
+    Product, ProductHistory, RejectReason, Snapshot, TrackingEvent, MAX_BATCH_SIZE,
+};
+
+// ─── Test helpers ─────────────────────────────────────────────────────────────
+
+const METADATA_SNAPSHOT: &str = include_str!(
+    "../../contracts/test_snapshots/test/test_add_tracking_event_with_metadata.1.json"
+);
+
+const SPLIT_SNAPSHOT: &str = include_str!(
+    "../../contracts/test_snapshots/test/test_split_product_creates_children_and_deactivates_parent.1.json"
+);
+
+fn event(event_id: u64, product_id: &str, event_type: &str, location: Location) -> TrackingEvent {
+    TrackingEvent {
+        event_id,
+        product_id: product_id.into(),
+        actor: "GOWNER".into(),
+        timestamp: 1_700_000_000 + event_id * 3600,
+        location,
+        event_type: event_type.into(),
+        data_hash: "00".repeat(32),
+        note: String::new(),
+        metadata: BTreeMap::new(),
+    }
+}
+
+fn history(product_id: &str, events: Vec<TrackingEvent>) -> ProductHistory {
+    ProductHistory {
+        product: Product {
+            id: product_id.into(),
+            name: "Organic Coffee Beans".into(),
+            active: true,
+            ..Product::default()
+        },
+        events,
+        parents: Vec::new(),
+        children: Vec::new(),
+    }
+}
+
+fn hamburg_terminal() -> Location {
+    Location {
+        name: "Hamburg Container Terminal".into(),
+        facility_id: "4012345000009".into(),
+        country: "DE".into(),
+        geohash: "u1x0".into(),
+        coordinates: Some(Coordinates {
+            latitude: 53_534_000,
+            longitude: 9_937_000,
+        }),
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// VOCABULARY
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_vocab_is_case_insensitive() {
+    assert_eq!(map_event_type("SHIP"), map_event_type("ship"));
+    assert_eq!(map_event_type("shipped").biz_step, "shipping");
+    assert_eq!(map_event_type("shipped").disposition, Some("in_transit"));
+}
+
+#[test]
+fn test_vocab_lineage_events_are_transformations() {
+    for symbol in ["split", "merged", "transformed"] {
+        assert_eq!(
+            map_event_type(symbol).kind,
+            EpcisEventKind::TransformationEvent
+        );
+    }
+    assert_eq!(map_event_type("HARVEST").kind, EpcisEventKind::ObjectEvent);
+}
+
+#[test]
+fn test_vocab_unknown_event_falls_back_to_other() {
+    let mapping = map_event_type("customs_hold");
+    assert_eq!(mapping.biz_step, "other");
+    assert_eq!(mapping.disposition, None);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// EXPORT
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_format_timestamp() {
+    assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+    assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+    assert_eq!(format_timestamp(1_700_000_000), "2023-11-14T22:13:20Z");
+}
+
+#[test]
+fn test_export_object_event() {
+    let h = history(
+        "COFFEE-ETH-001",
+        vec![event(1, "COFFEE-ETH-001", "shipped", hamburg_terminal())],
+    );
+    let out = export_event(&h, &h.events[0], &ExportOptions::default());
+
+    assert_eq!(out["type"], "ObjectEvent");
+    assert_eq!(out["eventID"], "urn:chainlogistics:event:1");
+    assert_eq!(
+        out["epcList"][0],
+        "urn:chainlogistics:product:COFFEE-ETH-001"
+    );
+    assert_eq!(out["action"], "OBSERVE");
+    assert_eq!(out["bizStep"], "shipping");
+    assert_eq!(out["disposition"], "in_transit");
+    assert_eq!(
+        out["readPoint"]["id"],
+        "https://id.gs1.org/414/4012345000009"
+    );
+    // In transit: the goods are not at a business location.
+    assert!(out.get("bizLocation").is_none());
+    assert_eq!(out["cl:eventType"], "shipped");
+}
+
+#[test]
+fn test_export_location_fallbacks() {
+    let mut location = hamburg_terminal();
+    location.facility_id = "HH CT/7".into();
+    let h = history("P1", vec![event(1, "P1", "received", location.clone())]);
+    let out = export_event(&h, &h.events[0], &ExportOptions::default());
+    assert_eq!(
+        out["readPoint"]["id"],
+        "urn:chainlogistics:facility:HH%20CT%2F7"
+    );
+    assert_eq!(
+        out["bizLocation"]["id"],
+        "urn:chainlogistics:facility:HH%20CT%2F7"
+    );
+
+    location.facility_id.clear();
+    let h = history("P1", vec![event(1, "P1", "received", location)]);
+    let out = export_event(&h, &h.events[0], &ExportOptions::default());
+    assert_eq!(out["readPoint"]["id"], "geo:53.534000,9.937000");
+    assert!(out.get("bizLocation").is_none());
+
+    let h = history("P1", vec![event(1, "P1", "received", Location::default())]);
+    let out = export_event(&h, &h.events[0], &ExportOptions::default());
+    assert!(out.get("readPoint").is_none());
+}
+
+#[test]
+fn test_export_split_from_both_sides() {
+    let mut parent = history(
+        "LOT-1",
+        vec![
+            event(1, "LOT-1", "HARVEST", Location::default()),
+            event(4, "LOT-1", "split", Location::default()),
+        ],
+    );
+    parent.children = vec!["LOT-1A".into(), "LOT-1B".into()];
+    let mut child = history(
+        "LOT-1A",
+        vec![event(2, "LOT-1A", "split", Location::default())],
+    );
+    child.parents = vec!["LOT-1".into()];
+
+    let options = ExportOptions::default();
+    let consumed = export_event(&parent, &parent.events[1], &options);
+    assert_eq!(consumed["type"], "TransformationEvent");
+    assert_eq!(consumed["inputEPCList"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        consumed["outputEPCList"][1],
+        "urn:chainlogistics:product:LOT-1B"
+    );
+    assert!(consumed.get("action").is_none());
+
+    let created = export_event(&child, &child.events[0], &options);
+    assert_eq!(
+        created["inputEPCList"][0],
+        "urn:chainlogistics:product:LOT-1"
+    );
+    assert_eq!(
+        created["outputEPCList"][0],
+        "urn:chainlogistics:product:LOT-1A"
+    );
+}
+
+#[test]
+fn test_export_document_envelope() {
+    let h = history(
+        "P1",
+        vec![
+            event(1, "P1", "HARVEST", Location::default()),
+            event(2, "P1", "SHIP", Location::default()),
+        ],
+    );
+    let doc = export_document(&[h], &ExportOptions::default());
+
+    assert_eq!(doc["type"], "EPCISDocument");
+    assert_eq!(doc["schemaVersion"], "2.0");
+    assert_eq!(doc["@context"][0], crate::EPCIS_CONTEXT);
+    let events = doc["epcisBody"]["eventList"].as_array().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["action"], "ADD");
+    assert_eq!(events[0]["bizStep"], "commissioning");
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// SNAPSHOTS
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_snapshot_reads_products_and_events() {
+    let snapshot = Snapshot::parse(METADATA_SNAPSHOT).unwrap();
+    assert_eq!(snapshot.product_ids(), vec!["COFFEE-ETH-001".to_string()]);
+
+    let h = snapshot.history("COFFEE-ETH-001").unwrap();
+    assert_eq!(h.product.origin.name, "Yirgacheffe, Ethiopia");
+    assert_eq!(h.events.len(), 1);
+    assert_eq!(h.events[0].event_type, "HARVEST");
+    assert_eq!(
+        h.events[0].metadata.get("batch").map(String::as_str),
+        Some("B2024-001")
+    );
+
+    let doc = export_document(&[h], &ExportOptions::default());
+    let exported = &doc["epcisBody"]["eventList"][0];
+    assert_eq!(exported["cl:metadata"]["temperature"], "22.5");
+}
+
+#[test]
+fn test_snapshot_export_lists_split_outputs() {
+    let snapshot = Snapshot::parse(SPLIT_SNAPSHOT).unwrap();
+    let parent = snapshot.history("COFFEE-ETH-001").unwrap();
+    assert_eq!(
+        parent.children,
+        vec!["CASE-1".to_string(), "CASE-2".to_string()]
+    );
+
+    let child = snapshot.history("CASE-2").unwrap();
+    assert_eq!(child.parents, vec!["COFFEE-ETH-001".to_string()]);
+
+    let options = ExportOptions::default();
+    let consumed = export_event(&parent, &parent.events[0], &options);
+    assert_eq!(consumed["type"], "TransformationEvent");
+    assert_eq!(
+        consumed["inputEPCList"],
+        json!(["urn:chainlogistics:product:COFFEE-ETH-001"])
+    );
+    assert_eq!(
+        consumed["outputEPCList"],
+        json!([
+            "urn:chainlogistics:product:CASE-1",
+            "urn:chainlogistics:product:CASE-2"
+        ])
+    );
+
+    let created = export_event(&child, &child.events[0], &options);
+    assert_eq!(
+        created["outputEPCList"],
+        json!(["urn:chainlogistics:product:CASE-2"])
+    );
+}
+
+#[test]
+fn test_snapshot_structured_location() {
+    let json = r#"{"ledger":{"ledger_entries":[
+        [{},[{"data":{"contract_data":{
+            "key":{"vec":[{"symbol":"Product"},{"string":"P1"}]},
+            "val":{"map":[{"key":{"symbol":"id"},"val":{"string":"P1"}}]}}}},null]],
+        [{},[{"data":{"contract_data":{
+            "key":{"vec":[{"symbol":"Event"},{"u64":7}]},
+            "val":{"map":[
+                {"key":{"symbol":"event_id"},"val":{"u64":7}},
+                {"key":{"symbol":"event_type"},"val":{"symbol":"received"}},
+                {"key":{"symbol":"location"},"val":{"map":[
+                    {"key":{"symbol":"coordinates"},"val":{"vec":[{"map":[
+                        {"key":{"symbol":"latitude"},"val":{"i32":53534000}},
+                        {"key":{"symbol":"longitude"},"val":{"i32":9937000}}]}]}},
+                    {"key":{"symbol":"country"},"val":{"string":"DE"}},
+                    {"key":{"symbol":"facility_id"},"val":{"string":"4012345000009"}},
+                    {"key":{"symbol":"geohash"},"val":{"string":"u1x0"}},
+                    {"key":{"symbol":"name"},"val":{"string":"Hamburg Container Terminal"}}]}},
+                {"key":{"symbol":"product_id"},"val":{"string":"P1"}}]}}}},null]]]}}"#;
+    let snapshot = Snapshot::parse(json).unwrap();
+    let h = snapshot.history("P1").unwrap();
+    assert_eq!(h.events[0].location, hamburg_terminal());
+    assert_eq!(
+        snapshot.history("P2"),
+        Err(Error::ProductNotFound("P2".into()))
+    );
+}
+
+#[test]
+fn test_snapshot_rejects_non_snapshot_json() {
+    assert!(matches!(
+        Snapshot::parse("{}"),
+        Err(Error::MalformedEntry(_))
+    ));
+    assert!(matches!(
+        Snapshot::parse("not json"),
+        Err(Error::InvalidJson(_))
+    ));
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// IMPORT
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_import_round_trips_export() {
+    let mut shipped = event(2, "COFFEE-ETH-001", "shipped", hamburg_terminal());
+    shipped.note = "Left port".into();
+    shipped.metadata.insert("carrier".into(), "Maersk".into());
+    let h = history(
+        "COFFEE-ETH-001",
+        vec![
+            event(1, "COFFEE-ETH-001", "HARVEST", Location::default()),
+            shipped,
+        ],
+    );
+    let doc = export_document(&[h], &ExportOptions::default());
+
+    let options = ImportOptions {
+        record_provenance: false,
+        ..ImportOptions::default()
+    };
+    let imported = import_document(&doc, &options).unwrap();
+    assert_eq!(imported.len(), 2);
+    assert_eq!(imported[0].event_type, "HARVEST");
+    assert_eq!(imported[1].product_id, "COFFEE-ETH-001");
+    assert_eq!(imported[1].event_type, "shipped");
+    assert_eq!(imported[1].note, "Left port");
+    assert_eq!(imported[1].location.facility_id, "4012345000009");
+    assert_eq!(imported[1].location.name, "Hamburg Container Terminal");
+    assert_eq!(
+        imported[1].metadata.get("carrier").map(String::as_str),
+        Some("Maersk")
+    );
+    assert_eq!(imported[1].data_hash, "00".repeat(32));
+}
+
+#[test]
+fn test_import_foreign_event_uses_vocabulary() {
+    let doc = json!({
+        "type": "EPCISDocument",
+        "epcisBody": { "eventList": [{
+            "type": "ObjectEvent",
+            "eventID": "urn:uuid:6d1a2c2e",
+            "eventTime": "2024-03-01T10:00:00.000Z",
+            "epcList": ["urn:chainlogistics:product:LOT-1", "urn:chainlogistics:product:LOT-2"],
+            "action": "OBSERVE",
+            "bizStep": "https://ref.gs1.org/cbv/BizStep-receiving",
+            "disposition": "https://ref.gs1.org/cbv/Disp-returned",
+            "readPoint": { "id": "geo:53.534,-9.937" }
+        }]}
+    });
+    let imported = import_document(&doc, &ImportOptions::default()).unwrap();
+
+    assert_eq!(imported.len(), 2);
+    assert_eq!(imported[0].event_type, "returned");
+    assert_eq!(imported[1].product_id, "LOT-2");
+    assert_eq!(
+        imported[0].location.coordinates,
+        Some(Coordinates {
+            latitude: 53_534_000,
+            longitude: -9_937_000
+        })
+    );
+    assert_eq!(
+        imported[0].metadata.get("epcis_id").map(String::as_str),
+        Some("urn:uuid:6d1a2c2e")
+    );
+    // Without `cl:dataHash` the event content is hashed.
+    assert_eq!(imported[0].data_hash.len(), 64);
+    assert_eq!(imported[0].data_hash, imported[1].data_hash);
+}
+
+#[test]
+fn test_import_batches_split_in_document_order() {
+    let epcs: Vec<String> = (0..MAX_BATCH_SIZE + 3)
+        .map(|i| format!("urn:chainlogistics:product:LOT-{i}"))
+        .collect();
+    let doc = json!({
+        "epcisBody": { "eventList": [{
+            "type": "ObjectEvent",
+            "epcList": epcs,
+            "bizStep": "shipping"
+        }]}
+    });
+    let batches = import_batches(&doc, &ImportOptions::default()).unwrap();
+
+    assert_eq!(batches.len(), 2);
+    assert_eq!(batches[0].len(), MAX_BATCH_SIZE);
+    assert_eq!(batches[1].len(), 3);
+    assert_eq!(batches[0][0].product_id, "LOT-0");
+    assert_eq!(
+        batches[1][2].product_id,
+        format!("LOT-{}", MAX_BATCH_SIZE + 2)
+    );
+}
+
+#[test]
+fn test_import_rejects_whole_document_on_metadata_limits() {
+    let too_many: serde_json::Map<String, serde_json::Value> = (0..20)
+        .map(|i| (format!("field_{i}"), json!("x")))
+        .collect();
+    let doc = json!({
+        "epcisBody": { "eventList": [
+            {
+                "type": "ObjectEvent",
+                "eventID": "urn:uuid:ok",
+                "epcList": ["urn:chainlogistics:product:P1"],
+                "bizStep": "shipping"
+            },
+            {
+                // 20 fields plus the two provenance fields.
+                "type": "ObjectEvent",
+                "eventID": "urn:uuid:too-many",
+                "eventTime": "2024-03-01T10:00:00Z",
+                "epcList": ["urn:chainlogistics:product:P1"],
+                "bizStep": "shipping",
+                "cl:metadata": too_many
+            },
+            {
+                "type": "ObjectEvent",
+                "epcList": ["urn:chainlogistics:product:P1"],
+                "bizStep": "shipping",
+                "cl:metadata": { "batch": "x".repeat(257) }
+            },
+            { "type": "TransformationEvent" },
+            {
+                "type": "ObjectEvent",
+                "epcList": ["urn:epc:id:sgtin:0614141.107346.2017"],
+                "bizStep": "shipping"
+            }
+        ]}
+    });
+
+    let Err(Error::Rejected(rejections)) = import_document(&doc, &ImportOptions::default()) else {
+        panic!("expected rejections");
+    };
+    let reasons: Vec<(usize, RejectReason)> = rejections
+        .into_iter()
+        .map(|r| (r.index, r.reason))
+        .collect();
+    assert_eq!(
+        reasons,
+        vec![
+            (1, RejectReason::TooManyCustomFields(22)),
+            (2, RejectReason::CustomFieldValueTooLong("batch".into())),
+            (
+                3,
+                RejectReason::UnsupportedEventType("TransformationEvent".into())
+            ),
+            (
+                4,
+                RejectReason::UnrecognizedEpc("urn:epc:id:sgtin:0614141.107346.2017".into())
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_import_rejects_invalid_symbols() {
+    let doc = json!({
+        "epcisBody": { "eventList": [{
+            "type": "ObjectEvent",
+            "epcList": ["urn:chainlogistics:product:P1"],
+            "cl:eventType": "picked-up"
+        }]}
+    });
+    let Err(Error::Rejected(rejections)) = import_document(&doc, &ImportOptions::default()) else {
+        panic!("expected rejections");
+    };
+    assert_eq!(
+        rejections[0].reason,
+        RejectReason::InvalidEventType("picked-up".into())
+    );
+
+    assert_eq!(
+        import_document(&json!({}), &ImportOptions::default()),
+        Err(Error::NotEpcisDocument)
+    );
+}
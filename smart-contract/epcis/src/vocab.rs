@@ -0,0 +1,201 @@
+/// Which EPCIS event class a tracking event becomes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EpcisEventKind {
+    ObjectEvent,
+    TransformationEvent,
+}
+
+/// EPCIS `action` of an ObjectEvent.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EpcisAction {
+    Add,
+    Observe,
+    Delete,
+}
+
+impl EpcisAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EpcisAction::Add => "ADD",
+            EpcisAction::Observe => "OBSERVE",
+            EpcisAction::Delete => "DELETE",
+        }
+    }
+}
+
+/// How one contract event type maps onto the CBV 2.0 vocabularies.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct VocabMapping {
+    pub kind: EpcisEventKind,
+    pub action: EpcisAction,
+    /// CBV bizStep short name, e.g. `shipping`.
+    pub biz_step: &'static str,
+    /// CBV disposition short name; `None` leaves the disposition unchanged.
+    pub disposition: Option<&'static str>,
+}
+
+const fn object(
+    action: EpcisAction,
+    biz_step: &'static str,
+    disposition: Option<&'static str>,
+) -> VocabMapping {
+    VocabMapping {
+        kind: EpcisEventKind::ObjectEvent,
+        action,
+        biz_step,
+        disposition,
+    }
+}
+
+const fn transformation(biz_step: &'static str) -> VocabMapping {
+    VocabMapping {
+        kind: EpcisEventKind::TransformationEvent,
+        action: EpcisAction::Observe,
+        biz_step,
+        disposition: Some("active"),
+    }
+}
+
+/// Contract event `Symbol`s (lower-cased) and their EPCIS equivalents.
+///
+/// Both the verb (`ship`) and past-tense (`shipped`) spellings in use across
+/// the contract and its clients are listed.
+pub const VOCAB_TABLE: &[(&str, VocabMapping)] = &[
+    // --- Origin ---
+    (
+        "harvest",
+        object(EpcisAction::Add, "commissioning", Some("active")),
+    ),
+    (
+        "harvested",
+        object(EpcisAction::Add, "commissioning", Some("active")),
+    ),
+    (
+        "manufacture",
+        object(EpcisAction::Add, "commissioning", Some("active")),
+    ),
+    (
+        "manufactured",
+        object(EpcisAction::Add, "commissioning", Some("active")),
+    ),
+    (
+        "process",
+        object(EpcisAction::Observe, "other", Some("in_progress")),
+    ),
+    (
+        "processed",
+        object(EpcisAction::Observe, "other", Some("in_progress")),
+    ),
+    (
+        "pack",
+        object(EpcisAction::Observe, "packing", Some("in_progress")),
+    ),
+    (
+        "packed",
+        object(EpcisAction::Observe, "packing", Some("in_progress")),
+    ),
+    // --- Movement ---
+    (
+        "ship",
+        object(EpcisAction::Observe, "shipping", Some("in_transit")),
+    ),
+    (
+        "shipped",
+        object(EpcisAction::Observe, "shipping", Some("in_transit")),
+    ),
+    (
+        "dispatched",
+        object(EpcisAction::Observe, "departing", Some("in_transit")),
+    ),
+    (
+        "in_transit",
+        object(EpcisAction::Observe, "transporting", Some("in_transit")),
+    ),
+    (
+        "delivered",
+        object(EpcisAction::Observe, "arriving", Some("in_progress")),
+    ),
+    (
+        "receive",
+        object(EpcisAction::Observe, "receiving", Some("in_progress")),
+    ),
+    (
+        "received",
+        object(EpcisAction::Observe, "receiving", Some("in_progress")),
+    ),
+    (
+        "stored",
+        object(
+            EpcisAction::Observe,
+            "storing",
+            Some("sellable_not_accessible"),
+        ),
+    ),
+    // --- Quality ---
+    (
+        "inspected",
+        object(EpcisAction::Observe, "inspecting", None),
+    ),
+    (
+        "temp_check",
+        object(EpcisAction::Observe, "inspecting", None),
+    ),
+    (
+        "certified",
+        object(EpcisAction::Observe, "inspecting", Some("conformant")),
+    ),
+    (
+        "rejected",
+        object(EpcisAction::Observe, "inspecting", Some("non_conformant")),
+    ),
+    (
+        "telemetry",
+        object(EpcisAction::Observe, "sensor_reporting", None),
+    ),
+    (
+        "excursion",
+        object(
+            EpcisAction::Observe,
+            "sensor_reporting",
+            Some("non_conformant"),
+        ),
+    ),
+    // --- End of life ---
+    (
+        "sold",
+        object(EpcisAction::Observe, "retail_selling", Some("retail_sold")),
+    ),
+    (
+        "returned",
+        object(EpcisAction::Observe, "receiving", Some("returned")),
+    ),
+    (
+        "recall",
+        object(EpcisAction::Observe, "holding", Some("recalled")),
+    ),
+    (
+        "recalled",
+        object(EpcisAction::Observe, "holding", Some("recalled")),
+    ),
+    (
+        "disposed",
+        object(EpcisAction::Delete, "destroying", Some("destroyed")),
+    ),
+    // --- Lineage ---
+    ("split", transformation("disassembling")),
+    ("merged", transformation("assembling")),
+    ("transformed", transformation("commissioning")),
+];
+
+/// Fallback for event types missing from [`VOCAB_TABLE`].
+pub const UNMAPPED: VocabMapping = object(EpcisAction::Observe, "other", None);
+
+/// Look up the EPCIS mapping for a contract event type, ignoring case.
+pub fn map_event_type(event_type: &str) -> VocabMapping {
+    let key = event_type.to_ascii_lowercase();
+    VOCAB_TABLE
+        .iter()
+        .find(|(symbol, _)| *symbol == key)
+        .map(|(_, mapping)| *mapping)
+        .unwrap_or(UNMAPPED)
+}
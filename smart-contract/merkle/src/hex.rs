@@ -0,0 +1,39 @@
+use crate::tree::Hash;
+
+/// Lowercase hex, as the contract's `BytesN<32>` values appear in snapshots.
+pub fn to_hex(hash: &Hash) -> String {
+    hash.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Parse 64 hex characters, with or without a `0x` prefix.
+pub fn from_hex(s: &str) -> Option<Hash> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    if s.len() != 64 || !s.is_ascii() {
+        return None;
+    }
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(out)
+}
+
+/// Serde adapter writing a list of hashes as hex strings.
+pub(crate) mod serde_hashes {
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::{from_hex, to_hex};
+    use crate::tree::Hash;
+
+    pub fn serialize<S: Serializer>(hashes: &[Hash], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(hashes.iter().map(to_hex))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Hash>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|s| from_hex(s).ok_or_else(|| D::Error::custom(format!("invalid hash `{s}`"))))
+            .collect()
+    }
+}
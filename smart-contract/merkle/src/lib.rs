@@ -0,0 +1,19 @@
+//! Host-side Merkle inclusion proofs for ChainLogistics product events.
+//!
+//! The contract keeps, per product, an RFC 9162 Merkle tree whose leaves are
+//! the event hashes (SHA-256 of each event's XDR, the value linked by the
+//! next event's `prev_hash`). This crate rebuilds that tree from a product's
+//! event hashes, produces an inclusion proof for one event, and checks a
+//! proof against the root returned by `get_event_merkle_root_at`, so a single
+//! event can be verified with one ledger read.
+
+mod hex;
+mod proof;
+mod tree;
+
+#[cfg(test)]
+mod test;
+
+pub use hex::*;
+pub use proof::*;
+pub use tree::*;
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+use crate::tree::{leaf_hash, node_hash, Hash};
+
+/// Proof that one event is leaf `index` of a product tree with `tree_size`
+/// leaves. Check it against `get_event_merkle_root_at(product_id, tree_size)`,
+/// which stays valid after later events are added.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct InclusionProof {
+    pub index: u64,
+    pub tree_size: u64,
+    /// Sibling hashes from the leaf up to the root.
+    #[serde(with = "crate::hex::serde_hashes")]
+    pub path: Vec<Hash>,
+}
+
+impl InclusionProof {
+    /// The root implied by `event_hash` and this path, or `None` when the
+    /// path has the wrong length for `index` and `tree_size`.
+    ///
+    /// Follows the verification algorithm of RFC 9162 section 2.1.3.2.
+    pub fn root_for(&self, event_hash: &Hash) -> Option<Hash> {
+        if self.index >= self.tree_size {
+            return None;
+        }
+        let mut f_n = self.index;
+        let mut s_n = self.tree_size - 1;
+        let mut root = leaf_hash(event_hash);
+
+        for sibling in &self.path {
+            if s_n == 0 {
+                return None;
+            }
+            if f_n & 1 == 1 || f_n == s_n {
+                root = node_hash(sibling, &root);
+                while f_n & 1 == 0 && f_n != 0 {
+                    f_n >>= 1;
+                    s_n >>= 1;
+                }
+            } else {
+                root = node_hash(&root, sibling);
+            }
+            f_n >>= 1;
+            s_n >>= 1;
+        }
+
+        (s_n == 0).then_some(root)
+    }
+
+    /// Whether `event_hash` is included under `root`.
+    pub fn verify(&self, event_hash: &Hash, root: &Hash) -> bool {
+        self.root_for(event_hash).as_ref() == Some(root)
+    }
+}
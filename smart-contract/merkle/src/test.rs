@@ -0,0 +1,142 @@
+use crate::{empty_root, from_hex, leaf_hash, node_hash, to_hex, Hash, InclusionProof, MerkleTree};
+
+// ─── Test helpers ─────────────────────────────────────────────────────────────
+
+fn event_hash(i: u64) -> Hash {
+    let mut h = [0u8; 32];
+    h[..8].copy_from_slice(&i.to_be_bytes());
+    h[31] = 0xee;
+    h
+}
+
+fn tree(size: u64) -> MerkleTree {
+    MerkleTree::new((0..size).map(event_hash))
+}
+
+/// The contract's incremental algorithm: keep the peaks, merge on each set
+/// low bit of the old size, and fold the peaks smallest-first for the root.
+fn incremental_root(size: u64) -> Hash {
+    let mut peaks: Vec<Hash> = Vec::new();
+    for i in 0..size {
+        let mut node = leaf_hash(&event_hash(i));
+        let mut s = i;
+        while s & 1 == 1 {
+            node = node_hash(&peaks.pop().unwrap(), &node);
+            s >>= 1;
+        }
+        peaks.push(node);
+    }
+    match peaks.split_last() {
+        None => empty_root(),
+        Some((last, rest)) => rest
+            .iter()
+            .rev()
+            .fold(*last, |acc, peak| node_hash(peak, &acc)),
+    }
+}
+
+// ═══ TREE ═══
+
+#[test]
+fn test_root_matches_contract_incremental_root() {
+    for size in 0..=40 {
+        assert_eq!(tree(size).root(), incremental_root(size), "size {size}");
+    }
+}
+
+#[test]
+fn test_small_tree_shapes() {
+    assert_eq!(tree(0).root(), empty_root());
+    assert_eq!(
+        to_hex(&empty_root()),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+
+    let leaves: Vec<Hash> = (0..3).map(|i| leaf_hash(&event_hash(i))).collect();
+    assert_eq!(tree(1).root(), leaves[0]);
+    assert_eq!(
+        tree(3).root(),
+        node_hash(&node_hash(&leaves[0], &leaves[1]), &leaves[2])
+    );
+
+    let mut grown = tree(2);
+    grown.push(event_hash(2));
+    assert_eq!(grown, tree(3));
+}
+
+// ═══ PROOFS ═══
+
+#[test]
+fn test_every_proof_verifies() {
+    for size in 1..=40 {
+        let tree = tree(size);
+        let root = tree.root();
+        for index in 0..size {
+            let proof = tree.proof(index).unwrap();
+            assert!(
+                proof.verify(&event_hash(index), &root),
+                "size {size} index {index}"
+            );
+        }
+        assert!(tree.proof(size).is_none());
+    }
+}
+
+#[test]
+fn test_proof_rejects_wrong_event_index_or_root() {
+    let tree = tree(11);
+    let root = tree.root();
+    let proof = tree.proof(6).unwrap();
+
+    assert!(!proof.verify(&event_hash(5), &root));
+    assert!(!proof.verify(&event_hash(6), &self::tree(12).root()));
+
+    let moved = InclusionProof {
+        index: 7,
+        ..proof.clone()
+    };
+    assert!(!moved.verify(&event_hash(6), &root));
+
+    let mut short = proof.clone();
+    short.path.pop();
+    assert_eq!(short.root_for(&event_hash(6)), None);
+
+    let mut long = proof;
+    long.path.push([0u8; 32]);
+    assert_eq!(long.root_for(&event_hash(6)), None);
+}
+
+#[test]
+fn test_old_proof_checks_against_root_at_its_size() {
+    let proof = tree(5).proof(2).unwrap();
+    let later = tree(9);
+
+    assert!(!proof.verify(&event_hash(2), &later.root()));
+    assert!(proof.verify(&event_hash(2), &tree(proof.tree_size).root()));
+}
+
+#[test]
+fn test_proof_json_round_trip() {
+    let proof = tree(6).proof(4).unwrap();
+    let json = serde_json::to_value(&proof).unwrap();
+
+    assert_eq!(json["index"], 4);
+    assert_eq!(json["tree_size"], 6);
+    assert_eq!(json["path"][0], to_hex(&proof.path[0]));
+    assert_eq!(
+        serde_json::from_value::<InclusionProof>(json).unwrap(),
+        proof
+    );
+
+    let bad = serde_json::json!({ "index": 0, "tree_size": 2, "path": ["zz"] });
+    assert!(serde_json::from_value::<InclusionProof>(bad).is_err());
+}
+
+#[test]
+fn test_hex_parsing() {
+    let h = event_hash(3);
+    assert_eq!(from_hex(&to_hex(&h)), Some(h));
+    assert_eq!(from_hex(&format!("0x{}", to_hex(&h))), Some(h));
+    assert_eq!(from_hex("abcd"), None);
+    assert_eq!(from_hex(&"g".repeat(64)), None);
+}
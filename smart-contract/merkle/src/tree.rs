@@ -0,0 +1,107 @@
+use sha2::{Digest, Sha256};
+
+use crate::proof::InclusionProof;
+
+pub type Hash = [u8; 32];
+
+/// Leaf hash of an event: `SHA-256(0x00 || event_hash)`.
+pub fn leaf_hash(event_hash: &Hash) -> Hash {
+    Sha256::new()
+        .chain_update([0u8])
+        .chain_update(event_hash)
+        .finalize()
+        .into()
+}
+
+/// Interior node hash: `SHA-256(0x01 || left || right)`.
+pub fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    Sha256::new()
+        .chain_update([1u8])
+        .chain_update(left)
+        .chain_update(right)
+        .finalize()
+        .into()
+}
+
+/// Root of the empty tree: SHA-256 of the empty string.
+pub fn empty_root() -> Hash {
+    Sha256::digest([]).into()
+}
+
+/// A product's event tree, built from its event hashes in recording order.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MerkleTree {
+    leaves: Vec<Hash>,
+}
+
+impl MerkleTree {
+    pub fn new<I: IntoIterator<Item = Hash>>(event_hashes: I) -> Self {
+        Self {
+            leaves: event_hashes.into_iter().map(|h| leaf_hash(&h)).collect(),
+        }
+    }
+
+    /// Append the next event, as `add_tracking_event` does on-chain.
+    pub fn push(&mut self, event_hash: Hash) {
+        self.leaves.push(leaf_hash(&event_hash));
+    }
+
+    pub fn len(&self) -> u64 {
+        self.leaves.len() as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// The root the contract reports for a tree of this size.
+    pub fn root(&self) -> Hash {
+        if self.leaves.is_empty() {
+            return empty_root();
+        }
+        subtree_root(&self.leaves)
+    }
+
+    /// Inclusion proof for the event at `index` (0-based, in recording
+    /// order), or `None` when the index is past the end of the tree.
+    pub fn proof(&self, index: u64) -> Option<InclusionProof> {
+        if index >= self.len() {
+            return None;
+        }
+        let mut path = Vec::new();
+        audit_path(index as usize, &self.leaves, &mut path);
+        Some(InclusionProof {
+            index,
+            tree_size: self.len(),
+            path,
+        })
+    }
+}
+
+/// Largest power of two strictly below `n` (for `n > 1`).
+fn split_point(n: usize) -> usize {
+    1 << (usize::BITS - 1 - (n - 1).leading_zeros())
+}
+
+fn subtree_root(leaves: &[Hash]) -> Hash {
+    if leaves.len() == 1 {
+        return leaves[0];
+    }
+    let k = split_point(leaves.len());
+    node_hash(&subtree_root(&leaves[..k]), &subtree_root(&leaves[k..]))
+}
+
+/// RFC 9162 `PATH(m, D[n])`: siblings from the leaf up to the root.
+fn audit_path(m: usize, leaves: &[Hash], path: &mut Vec<Hash>) {
+    if leaves.len() == 1 {
+        return;
+    }
+    let k = split_point(leaves.len());
+    if m < k {
+        audit_path(m, &leaves[..k], path);
+        path.push(subtree_root(&leaves[k..]));
+    } else {
+        audit_path(m - k, &leaves[k..], path);
+        path.push(subtree_root(&leaves[..k]));
+    }
+}